@@ -1,24 +1,17 @@
-use std::collections::BTreeMap;
-
-use cumulus_primitives_core::ParaId;
 use jokeymon_runtime as runtime;
-use pallet_omni::types::{Diet, JokeymonCount, JokeymonSpeciesData, JokeymonSpeciesId, Region};
-use runtime::{AccountId, AuraId, Runtime as JokeymonRuntime, Signature, EXISTENTIAL_DEPOSIT};
+use runtime::{
+    genesis_config_presets::{development_genesis, World, DEV_SMALL_WORLD},
+    AccountId, AuraId, Signature,
+};
 use sc_chain_spec::{ChainSpecExtension, ChainSpecGroup};
 use sc_service::ChainType;
 use serde::{Deserialize, Serialize};
-use sp_core::{sr25519, Pair, Public};
-use sp_runtime::{
-    traits::{IdentifyAccount, Verify},
-    BoundedBTreeMap,
-};
+use sp_core::{Pair, Public};
+use sp_runtime::traits::{IdentifyAccount, Verify};
 
 /// Specialized `ChainSpec` for the normal parachain runtime.
 pub type ChainSpec = sc_service::GenericChainSpec<Extensions>;
 
-/// The default XCM version to set in genesis config.
-const SAFE_XCM_VERSION: u32 = xcm::prelude::XCM_VERSION;
-
 /// Helper function to generate a crypto pair from seed
 pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
     TPublic::Pair::from_string(&format!("//{}", seed), None)
@@ -68,17 +61,6 @@ pub fn template_session_keys(keys: AuraId) -> runtime::SessionKeys {
     runtime::SessionKeys { aura: keys }
 }
 
-/// Helper to generate demographics data
-pub fn demographics_data<T: pallet_omni::Config>(
-    data: Vec<(JokeymonSpeciesId, JokeymonCount)>,
-) -> BoundedBTreeMap<JokeymonSpeciesId, JokeymonCount, T::MaxSpeciesInRegion> {
-    let mut map = BTreeMap::new();
-    for (k, v) in data {
-        map.insert(k, v);
-    }
-    BoundedBTreeMap::try_from(map).expect("Issue creating BoundedBTreeMap in demographics data")
-}
-
 pub fn development_config() -> ChainSpec {
     // Give your base currency a unit name and decimal places
     let mut properties = sc_chain_spec::Properties::new();
@@ -97,35 +79,7 @@ pub fn development_config() -> ChainSpec {
     .with_name("Development")
     .with_id("dev")
     .with_chain_type(ChainType::Development)
-    .with_genesis_config_patch(testnet_genesis(
-        // initial collators.
-        vec![
-            (
-                get_account_id_from_seed::<sr25519::Public>("Alice"),
-                get_collator_keys_from_seed("Alice"),
-            ),
-            (
-                get_account_id_from_seed::<sr25519::Public>("Bob"),
-                get_collator_keys_from_seed("Bob"),
-            ),
-        ],
-        vec![
-            get_account_id_from_seed::<sr25519::Public>("Alice"),
-            get_account_id_from_seed::<sr25519::Public>("Bob"),
-            get_account_id_from_seed::<sr25519::Public>("Charlie"),
-            get_account_id_from_seed::<sr25519::Public>("Dave"),
-            get_account_id_from_seed::<sr25519::Public>("Eve"),
-            get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-            get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-        ],
-        get_account_id_from_seed::<sr25519::Public>("Alice"),
-        1000.into(),
-    ))
+    .with_genesis_config_preset_name(DEV_SMALL_WORLD)
     .build()
 }
 
@@ -148,113 +102,25 @@ pub fn local_testnet_config() -> ChainSpec {
     .with_name("Local Testnet")
     .with_id("local_testnet")
     .with_chain_type(ChainType::Local)
-    .with_genesis_config_patch(testnet_genesis(
-        // initial collators.
-        vec![
-            (
-                get_account_id_from_seed::<sr25519::Public>("Alice"),
-                get_collator_keys_from_seed("Alice"),
-            ),
-            (
-                get_account_id_from_seed::<sr25519::Public>("Bob"),
-                get_collator_keys_from_seed("Bob"),
-            ),
-        ],
-        vec![
-            get_account_id_from_seed::<sr25519::Public>("Alice"),
-            get_account_id_from_seed::<sr25519::Public>("Bob"),
-            get_account_id_from_seed::<sr25519::Public>("Charlie"),
-            get_account_id_from_seed::<sr25519::Public>("Dave"),
-            get_account_id_from_seed::<sr25519::Public>("Eve"),
-            get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-            get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-        ],
-        get_account_id_from_seed::<sr25519::Public>("Alice"),
-        1000.into(),
-    ))
+    .with_genesis_config_preset_name(DEV_SMALL_WORLD)
     .with_protocol_id("template-local")
     .with_properties(properties)
     .build()
 }
 
-fn testnet_genesis(
-    invulnerables: Vec<(AccountId, AuraId)>,
-    endowed_accounts: Vec<AccountId>,
-    root: AccountId,
-    id: ParaId,
-) -> serde_json::Value {
-    serde_json::json!({
-        "balances": {
-            "balances": endowed_accounts.iter().cloned().map(|k| (k, 1u64 << 60)).collect::<Vec<_>>(),
-        },
-        "parachainInfo": {
-            "parachainId": id,
-        },
-        "collatorSelection": {
-            "invulnerables": invulnerables.iter().cloned().map(|(acc, _)| acc).collect::<Vec<_>>(),
-            "candidacyBond": EXISTENTIAL_DEPOSIT * 16,
-        },
-        "session": {
-            "keys": invulnerables
-                .into_iter()
-                .map(|(acc, aura)| {
-                    (
-                        acc.clone(),                 // account id
-                        acc,                         // validator id
-                        template_session_keys(aura), // session keys
-                    )
-                })
-            .collect::<Vec<_>>(),
-        },
-        "polkadotXcm": {
-            "safeXcmVersion": Some(SAFE_XCM_VERSION),
+/// A development chain with the regions and species of `world`
+pub fn world_config(world: &World) -> ChainSpec {
+    ChainSpec::builder(
+        runtime::WASM_BINARY.expect("WASM binary was not built, please build it!"),
+        Extensions {
+            relay_chain: "rococo-local".into(),
+            // You MUST set this to the correct network!
+            para_id: 1000,
         },
-        "sudo": { "key": Some(root) },
-        "omniPallet": {
-            "regionIdToRegion": vec![
-                (0u32, Region::<JokeymonRuntime> {
-                    id : 0u32,
-                    total_population : 450,
-                    population_demographics : demographics_data::<JokeymonRuntime> (
-                    vec![
-                    (0u32, 150u32),
-                    (1u32, 150u32),
-                    (2u32, 150u32),
-                    ]
-                    ),
-                    energy_yield : 100_000u32,
-                    latitude : 0u32,
-                    longitude : 0u32,
-                }),
-                ],
-                "speciesIdToData": vec![
-                    (0u32, JokeymonSpeciesData {
-                        id: 0u32,
-                        avg_weight: 10,
-                        avg_daily_food_consumption: 10,
-                        diet: Diet::Herbivore,
-                        evolves_to: Some(1u32),
-                    }),
-                    (1u32, JokeymonSpeciesData {
-                        id: 1u32,
-                        avg_weight: 20,
-                        avg_daily_food_consumption: 25,
-                        diet: Diet::Herbivore,
-                        evolves_to: Some(2u32),
-                    }),
-                    (2u32, JokeymonSpeciesData {
-                        id: 2u32,
-                        avg_weight: 30,
-                        avg_daily_food_consumption: 45,
-                        diet: Diet::Carnivore,
-                        evolves_to: None,
-                    }),
-                ]
-        }
-    })
+    )
+    .with_name("Development")
+    .with_id("dev")
+    .with_chain_type(ChainType::Development)
+    .with_genesis_config_patch(development_genesis(world))
+    .build()
 }
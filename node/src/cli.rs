@@ -38,6 +38,15 @@ pub enum Subcommand {
     /// The pallet benchmarking moved to the `pallet` sub-command.
     #[command(subcommand)]
     Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+    /// Generate a world from a seed, as a genesis patch or a world definition.
+    GenerateWorld(crate::world::GenerateWorldCmd),
+
+    /// Simulate the population of every region for a number of blocks.
+    SimulateEcosystem(crate::simulate::SimulateEcosystemCmd),
+
+    /// Export the regions, species, accounts and jokeymon of a given block as readable JSON.
+    ExportGameState(crate::game_state::ExportGameStateCmd),
 }
 
 const AFTER_HELP_EXAMPLE: &str = color_print::cstr!(
@@ -50,6 +59,12 @@ const AFTER_HELP_EXAMPLE: &str = color_print::cstr!(
            Launch a full node with default parachain <italic>local-testnet</> and relay chain <italic>rococo-local</>.
    <bold>jokeymon-node --collator</>
            Launch a collator with default parachain <italic>local-testnet</> and relay chain <italic>rococo-local</>.
+   <bold>jokeymon-node generate-world --seed 42 --definition -o world.toml</>
+           Generate a world, then launch it with <italic>--chain world:world.toml</>.
+   <bold>jokeymon-node simulate-ecosystem --chain world:world.toml --blocks 5000 --gamma 150</>
+           Print how every region's populations evolve with a different carnivore death rate.
+   <bold>jokeymon-node export-game-state 1000 -o game.json</>
+           Write the regions, species, accounts and jokeymon at block 1000 as readable JSON.
  "#
 );
 #[derive(Debug, clap::Parser)]
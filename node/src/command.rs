@@ -1,4 +1,4 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, path::Path};
 
 use cumulus_client_service::storage_proof_size::HostFunctions as ReclaimHostFunctions;
 use cumulus_primitives_core::ParaId;
@@ -15,16 +15,25 @@ use crate::{
     chain_spec,
     cli::{Cli, RelayChainCli, Subcommand},
     service::new_partial,
+    world::WorldDefinition,
 };
 
-fn load_spec(id: &str) -> std::result::Result<Box<dyn ChainSpec>, String> {
+/// Load a named chain spec, a chain spec file, or a development chain with the world of a
+/// definition file given as `world:<path>`
+pub(crate) fn load_spec(id: &str) -> std::result::Result<Box<dyn ChainSpec>, String> {
     Ok(match id {
         "dev" => Box::new(chain_spec::development_config()),
         "template-rococo" => Box::new(chain_spec::local_testnet_config()),
         "" | "local" => Box::new(chain_spec::local_testnet_config()),
-        path => Box::new(chain_spec::ChainSpec::from_json_file(
-            std::path::PathBuf::from(path),
-        )?),
+        id => match id.strip_prefix("world:") {
+            Some(path) => {
+                let world = WorldDefinition::from_file(Path::new(path))?.into_world()?;
+                Box::new(chain_spec::world_config(&world))
+            }
+            None => Box::new(chain_spec::ChainSpec::from_json_file(
+                std::path::PathBuf::from(id),
+            )?),
+        },
     })
 }
 
@@ -135,6 +144,9 @@ pub fn run() -> Result<()> {
                 Ok(cmd.run(components.client, config.chain_spec))
             })
         }
+        Some(Subcommand::ExportGameState(cmd)) => {
+            construct_async_run!(|components, cli, cmd, config| Ok(cmd.run(components.client)))
+        }
         Some(Subcommand::ImportBlocks(cmd)) => {
             construct_async_run!(|components, cli, cmd, config| {
                 Ok(cmd.run(components.client, components.import_queue))
@@ -181,6 +193,8 @@ pub fn run() -> Result<()> {
                 cmd.run(&*spec)
             })
         }
+        Some(Subcommand::GenerateWorld(cmd)) => Ok(cmd.run()?),
+        Some(Subcommand::SimulateEcosystem(cmd)) => Ok(cmd.run()?),
         Some(Subcommand::Benchmark(cmd)) => {
             let runner = cli.create_runner(cmd)?;
             // Switch on the concrete benchmark sub-command-
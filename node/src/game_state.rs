@@ -0,0 +1,164 @@
+//! The `export-game-state` command
+//!
+//! Decodes the omni pallet's storage at a block into a readable JSON document, unlike
+//! `export-state` which writes raw storage.
+
+use std::{io::Write, path::PathBuf, sync::Arc};
+
+use frame_support::traits::PalletInfoAccess;
+use jokeymon_runtime::{Block, BlockNumber, OmniPallet, Runtime};
+use pallet_omni::{
+    types::{JokeymonId, JokeymonSpeciesData, JokeymonSpeciesId, Region, RegionId},
+    AccountToData, JokeymonIdToData, JokeymonIdToOwner, RegionIdToRegion, SpeciesIdToSpeciesData,
+};
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::{HeaderBackend, StorageProvider, UsageProvider};
+use serde::Serialize;
+use sp_core::{storage::StorageKey, twox_128};
+use sp_runtime::Storage;
+
+/// The `export-game-state` command
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportGameStateCmd {
+    /// Block hash or number, the best block when not given
+    #[arg(value_name = "HASH or NUMBER")]
+    pub input: Option<BlockNumberOrHash>,
+
+    /// File to write to instead of stdout
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub pruning_params: PruningParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: DatabaseParams,
+}
+
+/// The game at a block
+#[derive(Debug, Serialize)]
+struct GameState {
+    block_number: BlockNumber,
+    block_hash: String,
+    species: Vec<JokeymonSpeciesData>,
+    regions: Vec<Region<Runtime>>,
+    accounts: Vec<AccountState>,
+    jokeymon: Vec<JokeymonState>,
+}
+
+/// An account and the jokeymon it holds
+#[derive(Debug, Serialize)]
+struct AccountState {
+    account: String,
+    current_region: RegionId,
+    party: Vec<JokeymonId>,
+    /// Held jokeymon that are not in the party
+    boxed: Vec<JokeymonId>,
+}
+
+#[derive(Debug, Serialize)]
+struct JokeymonState {
+    id: JokeymonId,
+    species: JokeymonSpeciesId,
+    owner: Option<String>,
+    birth_date: BlockNumber,
+}
+
+impl ExportGameStateCmd {
+    /// Run the `export-game-state` command
+    pub async fn run<BA, C>(&self, client: Arc<C>) -> sc_cli::Result<()>
+    where
+        C: UsageProvider<Block> + StorageProvider<Block, BA> + HeaderBackend<Block>,
+        BA: sc_client_api::backend::Backend<Block>,
+    {
+        let block_id = self.input.as_ref().map(|b| b.parse()).transpose()?;
+        let hash = match block_id {
+            Some(id) => client.expect_block_hash_from_id(&id)?,
+            None => client.usage_info().chain.best_hash,
+        };
+        let block_number = client
+            .number(hash)?
+            .ok_or_else(|| format!("unknown block {hash}"))?;
+
+        // only the omni pallet's storage is needed to decode it
+        let prefix = StorageKey(twox_128(OmniPallet::name().as_bytes()).to_vec());
+        let top = client
+            .storage_pairs(hash, Some(&prefix), None)?
+            .map(|(key, value)| (key.0, value.0))
+            .collect();
+        let storage = Storage {
+            top,
+            children_default: Default::default(),
+        };
+
+        let state = sp_io::TestExternalities::new(storage).execute_with(|| {
+            let mut species: Vec<_> = SpeciesIdToSpeciesData::<Runtime>::iter_values().collect();
+            species.sort_by_key(|data| data.id);
+            let mut regions: Vec<_> = RegionIdToRegion::<Runtime>::iter_values().collect();
+            regions.sort_by_key(|region| region.id);
+            let mut accounts: Vec<_> = AccountToData::<Runtime>::iter()
+                .map(|(who, data)| AccountState {
+                    account: who.to_string(),
+                    current_region: data.current_region,
+                    boxed: data
+                        .jokeymon
+                        .iter()
+                        .filter(|id| !data.party.contains(id))
+                        .copied()
+                        .collect(),
+                    party: data.party.into_inner(),
+                })
+                .collect();
+            accounts.sort_by(|a, b| a.account.cmp(&b.account));
+            let mut jokeymon: Vec<_> = JokeymonIdToData::<Runtime>::iter()
+                .map(|(id, data)| JokeymonState {
+                    id,
+                    species: data.id,
+                    owner: JokeymonIdToOwner::<Runtime>::get(id).map(|who| who.to_string()),
+                    birth_date: data.birth_date,
+                })
+                .collect();
+            jokeymon.sort_by_key(|jokeymon| jokeymon.id);
+
+            GameState {
+                block_number,
+                block_hash: format!("{hash:?}"),
+                species,
+                regions,
+                accounts,
+                jokeymon,
+            }
+        });
+
+        let json = serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?;
+        match &self.output {
+            Some(path) => std::fs::write(path, json)?,
+            None => {
+                if std::io::stdout().write_all(json.as_bytes()).is_err() {
+                    let _ = std::io::stderr().write_all(b"Error writing to stdout\n");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CliConfiguration for ExportGameStateCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn pruning_params(&self) -> Option<&PruningParams> {
+        Some(&self.pruning_params)
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
@@ -5,8 +5,11 @@
 mod chain_spec;
 mod cli;
 mod command;
+mod game_state;
 mod rpc;
 mod service;
+mod simulate;
+mod world;
 
 fn main() -> sc_cli::Result<()> {
     command::run()
@@ -5,15 +5,25 @@
 
 #![warn(missing_docs)]
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
-use jokeymon_runtime::{opaque::Block, AccountId, Balance, Nonce};
+use jokeymon_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Nonce};
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    types::{error::ErrorObject, ErrorObjectOwned},
+};
+use pallet_omni::{
+    runtime_api::OmniApi as OmniRuntimeApi,
+    types::{JokeymonCount, JokeymonSpeciesId, Rating, RegionId},
+};
 
 pub use sc_rpc::DenyUnsafe;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_runtime::{traits::Block as BlockT, Permill};
 
 /// A type representing all RPC extensions.
 pub type RpcExtension = jsonrpsee::RpcModule<()>;
@@ -42,6 +52,7 @@ where
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
     C::Api: BlockBuilder<Block>,
+    C::Api: OmniRuntimeApi<Block, AccountId, BlockNumber>,
     P: TransactionPool + Sync + Send + 'static,
 {
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
@@ -55,6 +66,95 @@ where
     } = deps;
 
     module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
-    module.merge(TransactionPayment::new(client).into_rpc())?;
+    module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+    module.merge(Omni::new(client).into_rpc())?;
     Ok(module)
 }
+
+/// Queries into the jokeymon world
+#[rpc(client, server)]
+pub trait OmniApi<BlockHash> {
+    /// A page of the current season leaderboard, highest rated first
+    #[method(name = "omni_leaderboard")]
+    fn leaderboard(
+        &self,
+        page: u32,
+        page_size: u32,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<(AccountId, Rating)>>;
+
+    /// Share of the species living in each region that an account has caught
+    #[method(name = "omni_pokedexCompletion")]
+    fn pokedex_completion(
+        &self,
+        who: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<(RegionId, Permill)>>;
+
+    /// The sampled populations of a region, oldest first
+    #[method(name = "omni_populationHistory")]
+    fn population_history(
+        &self,
+        region_id: RegionId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<(BlockNumber, BTreeMap<JokeymonSpeciesId, JokeymonCount>)>>;
+}
+
+/// Serves the omni pallet runtime api over RPC
+pub struct Omni<C> {
+    client: Arc<C>,
+}
+
+impl<C> Omni<C> {
+    /// Create a new omni RPC handler
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client }
+    }
+}
+
+fn runtime_error(error: impl ToString) -> ErrorObjectOwned {
+    ErrorObject::owned(1, "Omni runtime api call failed", Some(error.to_string()))
+}
+
+impl<C> OmniApiServer<<Block as BlockT>::Hash> for Omni<C>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: OmniRuntimeApi<Block, AccountId, BlockNumber>,
+{
+    fn leaderboard(
+        &self,
+        page: u32,
+        page_size: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<(AccountId, Rating)>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        self.client
+            .runtime_api()
+            .leaderboard(at, page, page_size)
+            .map_err(runtime_error)
+    }
+
+    fn pokedex_completion(
+        &self,
+        who: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<(RegionId, Permill)>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        self.client
+            .runtime_api()
+            .pokedex_completion(at, who)
+            .map_err(runtime_error)
+    }
+
+    fn population_history(
+        &self,
+        region_id: RegionId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<(BlockNumber, BTreeMap<JokeymonSpeciesId, JokeymonCount>)>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        self.client
+            .runtime_api()
+            .population_history(at, region_id)
+            .map_err(runtime_error)
+    }
+}
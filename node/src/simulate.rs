@@ -0,0 +1,188 @@
+//! The `simulate-ecosystem` command
+//!
+//! Runs the omni pallet's population update natively over the regions of a chain spec, so the
+//! Lotka-Volterra coefficients can be tuned without running a chain.
+
+use std::{collections::BTreeMap, fmt::Write, path::PathBuf};
+
+use jokeymon_runtime::Runtime;
+use pallet_omni::{
+    types::{JokeymonCount, JokeymonSpeciesId, PopulationModel, RegionId},
+    Pallet as OmniPallet, RegionIdToRegion,
+};
+use serde::Serialize;
+use sp_runtime::BuildStorage;
+
+/// Format of the simulated time series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SeriesFormat {
+    /// One `block,region,species,population` row per sample
+    Csv,
+    /// Every region with a population series per species
+    Json,
+}
+
+/// The `simulate-ecosystem` command
+#[derive(Debug, clap::Parser)]
+pub struct SimulateEcosystemCmd {
+    /// Chain to take the world from, `dev`, `local`, `world:<path>` or a chain spec file
+    ///
+    /// A raw chain spec, such as the output of `export-state`, simulates from that state.
+    #[arg(long, default_value = "dev")]
+    pub chain: String,
+
+    /// Number of blocks to simulate
+    #[arg(long, default_value_t = 1_000)]
+    pub blocks: u32,
+
+    /// Record the populations every this many blocks
+    #[arg(long, default_value_t = 1)]
+    pub every: u32,
+
+    #[arg(long, value_enum, default_value_t = SeriesFormat::Csv)]
+    pub format: SeriesFormat,
+
+    /// File to write to instead of stdout
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+
+    /// Changes are divided by this before they are applied
+    #[arg(long, default_value_t = PopulationModel::DEFAULT.timestep)]
+    pub timestep: i32,
+
+    /// Herbivore growth rate
+    #[arg(long, default_value_t = PopulationModel::DEFAULT.alpha)]
+    pub alpha: i32,
+
+    /// Rate carnivores eat herbivores
+    #[arg(long, default_value_t = PopulationModel::DEFAULT.beta)]
+    pub beta: i32,
+
+    /// Carnivore growth from eating
+    #[arg(long, default_value_t = PopulationModel::DEFAULT.delta)]
+    pub delta: i32,
+
+    /// Carnivore death rate
+    #[arg(long, default_value_t = PopulationModel::DEFAULT.gamma)]
+    pub gamma: i32,
+}
+
+/// The populations of one region over the simulation
+#[derive(Debug, Serialize)]
+struct RegionSeries {
+    region: RegionId,
+    species: BTreeMap<JokeymonSpeciesId, Vec<JokeymonCount>>,
+}
+
+/// Every recorded block with the populations at that block
+#[derive(Debug, Default, Serialize)]
+struct Series {
+    blocks: Vec<u32>,
+    regions: Vec<RegionSeries>,
+}
+
+impl SimulateEcosystemCmd {
+    pub fn run(&self) -> Result<(), String> {
+        if self.every == 0 || self.timestep <= 0 {
+            return Err("--every and --timestep must be above zero".into());
+        }
+        // the model saturates instead of overflowing, but negative rates have no meaning
+        if [self.alpha, self.beta, self.delta, self.gamma]
+            .iter()
+            .any(|rate| *rate < 0)
+        {
+            return Err("--alpha, --beta, --delta and --gamma can't be negative".into());
+        }
+        let spec = crate::command::load_spec(&self.chain)?;
+        let storage = spec.build_storage()?;
+        let model = PopulationModel {
+            timestep: self.timestep,
+            alpha: self.alpha,
+            beta: self.beta,
+            delta: self.delta,
+            gamma: self.gamma,
+        };
+
+        let series = sp_io::TestExternalities::new(storage).execute_with(|| {
+            let mut series = Series::default();
+            series.record(0);
+            for block in 1..=self.blocks {
+                OmniPallet::<Runtime>::update_populations(&model);
+                if block % self.every == 0 {
+                    series.record(block);
+                }
+            }
+            series
+        });
+
+        let contents = match self.format {
+            SeriesFormat::Csv => series.to_csv(),
+            SeriesFormat::Json => {
+                serde_json::to_string_pretty(&series).map_err(|e| e.to_string())?
+            }
+        };
+        match &self.output {
+            Some(path) => std::fs::write(path, contents)
+                .map_err(|e| format!("failed to write {}: {e}", path.display())),
+            None => {
+                print!("{contents}");
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Series {
+    /// add the populations in storage as the sample for `block`
+    fn record(&mut self, block: u32) {
+        let samples = self.blocks.len();
+        self.blocks.push(block);
+        let mut regions: Vec<_> = RegionIdToRegion::<Runtime>::iter().collect();
+        regions.sort_by_key(|(id, _)| *id);
+        for (region_id, region) in regions {
+            let index = match self.regions.binary_search_by_key(&region_id, |r| r.region) {
+                Ok(index) => index,
+                Err(index) => {
+                    self.regions.insert(
+                        index,
+                        RegionSeries {
+                            region: region_id,
+                            species: BTreeMap::new(),
+                        },
+                    );
+                    index
+                }
+            };
+            let series = &mut self.regions[index];
+            for (species_id, count) in region.population_demographics.iter() {
+                // species that arrive later have no population before
+                let populations = series
+                    .species
+                    .entry(*species_id)
+                    .or_insert_with(|| vec![0; samples]);
+                populations.push(*count);
+            }
+            // species that left the region have no population now
+            for populations in series.species.values_mut() {
+                populations.resize(samples + 1, 0);
+            }
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("block,region,species,population\n");
+        for (sample, block) in self.blocks.iter().enumerate() {
+            for region in &self.regions {
+                for (species_id, populations) in &region.species {
+                    let _ = writeln!(
+                        csv,
+                        "{block},{},{species_id},{}",
+                        region.region,
+                        populations.get(sample).copied().unwrap_or_default()
+                    );
+                }
+            }
+        }
+        csv
+    }
+}
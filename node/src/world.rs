@@ -0,0 +1,451 @@
+//! World definition files
+//!
+//! A world definition lists the regions and species of a chain in JSON or TOML, so designers
+//! can change the world without touching Rust. It is checked against the runtime's limits
+//! before it is turned into the `omniPallet` genesis section.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use jokeymon_runtime::{genesis_config_presets::World, Runtime};
+use pallet_omni::{
+    types::{
+        Coordinate, Diet, JokeymonCount, JokeymonSpeciesData, JokeymonSpeciesId, Region, RegionId,
+    },
+    worldgen::{self, GeneratedWorld, WorldParams},
+};
+use serde::{Deserialize, Serialize};
+
+/// The regions and species of a world, as written in a definition file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorldDefinition {
+    pub species: Vec<SpeciesDefinition>,
+    pub regions: Vec<RegionDefinition>,
+}
+
+/// A species of a world definition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpeciesDefinition {
+    pub id: JokeymonSpeciesId,
+    pub avg_weight: u16,
+    pub avg_daily_food_consumption: u16,
+    pub diet: Diet,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evolves_to: Option<JokeymonSpeciesId>,
+}
+
+/// A region of a world definition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegionDefinition {
+    pub id: RegionId,
+    #[serde(default)]
+    pub latitude: Coordinate,
+    #[serde(default)]
+    pub longitude: Coordinate,
+    pub energy_yield: u32,
+    /// Regions reachable from this one, checked but dropped since it is not stored on chain
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub adjacent: Vec<RegionId>,
+    /// Must match the sum of the demographics when given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_population: Option<u64>,
+    pub demographics: Vec<Population>,
+}
+
+/// How many of a species live in a region
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Population {
+    pub species: JokeymonSpeciesId,
+    pub count: JokeymonCount,
+}
+
+impl WorldDefinition {
+    /// Read a definition, as TOML if the file ends in `.toml` and as JSON otherwise
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read world definition {}: {e}", path.display()))?;
+        let parsed = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&contents).map_err(|e| e.to_string()),
+            _ => serde_json::from_str(&contents).map_err(|e| e.to_string()),
+        };
+        parsed.map_err(|e| format!("invalid world definition {}: {e}", path.display()))
+    }
+
+    /// Check the definition can be used as genesis
+    ///
+    /// On top of the pallet's own genesis checks:
+    /// - no region has more species than `MaxSpeciesInRegion`, or a species twice
+    /// - a given `total_population` matches the sum of the demographics
+    /// - adjacency only refers to regions that exist, and no region is adjacent to itself
+    pub fn validate(&self) -> Result<(), String> {
+        let region_ids: BTreeSet<_> = self.regions.iter().map(|region| region.id).collect();
+        let max_species = <Runtime as pallet_omni::Config>::MaxSpeciesInRegion::get() as usize;
+        for region in &self.regions {
+            if region.demographics.len() > max_species {
+                return Err(format!(
+                    "region {} has {} species, at most {max_species} are allowed",
+                    region.id,
+                    region.demographics.len()
+                ));
+            }
+            let mut seen = BTreeSet::new();
+            for population in &region.demographics {
+                if !seen.insert(population.species) {
+                    return Err(format!(
+                        "region {} lists species {} twice",
+                        region.id, population.species
+                    ));
+                }
+            }
+            if let Some(total) = region.total_population {
+                let sum = region.population_sum();
+                if total != sum {
+                    return Err(format!(
+                        "region {} has a total population of {total} but its demographics add up to {sum}",
+                        region.id
+                    ));
+                }
+            }
+            for adjacent in &region.adjacent {
+                if *adjacent == region.id {
+                    return Err(format!("region {} is adjacent to itself", region.id));
+                }
+                if !region_ids.contains(adjacent) {
+                    return Err(format!(
+                        "region {} is adjacent to unknown region {adjacent}",
+                        region.id
+                    ));
+                }
+            }
+        }
+        let world = self.to_world();
+        pallet_omni::GenesisConfig::<Runtime> {
+            region_id_to_region: world
+                .regions
+                .into_iter()
+                .map(|region| (region.id, region))
+                .collect(),
+            species_id_to_data: world
+                .species
+                .into_iter()
+                .map(|species| (species.id, species))
+                .collect(),
+        }
+        .check()
+    }
+
+    /// Validate the definition and turn it into a runtime world
+    ///
+    /// Adjacency is not stored on chain yet, so it is dropped with a warning.
+    pub fn into_world(self) -> Result<World, String> {
+        self.validate()?;
+        if self
+            .regions
+            .iter()
+            .any(|region| !region.adjacent.is_empty())
+        {
+            log::warn!("world definition has region adjacency, which is not stored on chain yet");
+        }
+        Ok(self.to_world())
+    }
+
+    /// The runtime world of a definition that fits in the runtime's bounds
+    fn to_world(&self) -> World {
+        let regions = self
+            .regions
+            .iter()
+            .map(|region| {
+                let demographics: BTreeMap<_, _> = region
+                    .demographics
+                    .iter()
+                    .map(|population| (population.species, population.count))
+                    .collect();
+                Region::<Runtime> {
+                    id: region.id,
+                    total_population: region.population_sum(),
+                    population_demographics: demographics
+                        .try_into()
+                        .expect("checked against MaxSpeciesInRegion; qed"),
+                    energy_yield: region.energy_yield,
+                    latitude: region.latitude,
+                    longitude: region.longitude,
+                }
+            })
+            .collect();
+        let species = self
+            .species
+            .iter()
+            .map(|species| JokeymonSpeciesData {
+                id: species.id,
+                avg_weight: species.avg_weight,
+                avg_daily_food_consumption: species.avg_daily_food_consumption,
+                diet: species.diet.clone(),
+                evolves_to: species.evolves_to,
+            })
+            .collect();
+        World { regions, species }
+    }
+}
+
+impl RegionDefinition {
+    fn population_sum(&self) -> u64 {
+        self.demographics
+            .iter()
+            .map(|population| population.count as u64)
+            .sum()
+    }
+}
+
+impl From<GeneratedWorld<Runtime>> for WorldDefinition {
+    fn from(world: GeneratedWorld<Runtime>) -> Self {
+        let adjacency: BTreeMap<_, _> = world.adjacency.into_iter().collect();
+        let regions = world
+            .regions
+            .into_iter()
+            .map(|region| RegionDefinition {
+                id: region.id,
+                latitude: region.latitude,
+                longitude: region.longitude,
+                energy_yield: region.energy_yield,
+                adjacent: adjacency.get(&region.id).cloned().unwrap_or_default(),
+                total_population: Some(region.total_population),
+                demographics: region
+                    .population_demographics
+                    .into_iter()
+                    .map(|(species, count)| Population { species, count })
+                    .collect(),
+            })
+            .collect();
+        let species = world
+            .species
+            .into_iter()
+            .map(|species| SpeciesDefinition {
+                id: species.id,
+                avg_weight: species.avg_weight,
+                avg_daily_food_consumption: species.avg_daily_food_consumption,
+                diet: species.diet,
+                evolves_to: species.evolves_to,
+            })
+            .collect();
+        WorldDefinition { species, regions }
+    }
+}
+
+/// The `generate-world` command
+///
+/// Generates a world from a seed, with every region starting at the equilibrium of the
+/// population model.
+#[derive(Debug, clap::Parser)]
+pub struct GenerateWorldCmd {
+    /// Seed of the generator, the same seed and sizes always give the same world
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// Regions along the longitude
+    #[arg(long, default_value_t = 10)]
+    pub width: u32,
+
+    /// Regions along the latitude
+    #[arg(long, default_value_t = 10)]
+    pub height: u32,
+
+    /// Number of herbivore species
+    #[arg(long, default_value_t = 12)]
+    pub herbivores: u32,
+
+    /// Number of carnivore species
+    #[arg(long, default_value_t = 4)]
+    pub carnivores: u32,
+
+    /// Write a world definition for `--chain world:<path>` instead of a genesis patch
+    #[arg(long)]
+    pub definition: bool,
+
+    /// File to write to instead of stdout, definitions ending in `.toml` are written as TOML
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+impl GenerateWorldCmd {
+    pub fn run(&self) -> Result<(), String> {
+        if self.herbivores == 0 || self.carnivores == 0 {
+            return Err("a world needs at least one herbivore and one carnivore".into());
+        }
+        let world = worldgen::generate::<Runtime>(&WorldParams {
+            seed: self.seed,
+            width: self.width,
+            height: self.height,
+            herbivores: self.herbivores,
+            carnivores: self.carnivores,
+        });
+
+        let toml = self
+            .output
+            .as_ref()
+            .is_some_and(|path| path.extension().is_some_and(|ext| ext == "toml"));
+        let contents = if !self.definition {
+            let patch = serde_json::json!({ "omniPallet": world.genesis_config() });
+            serde_json::to_string_pretty(&patch).map_err(|e| e.to_string())?
+        } else if toml {
+            toml::to_string_pretty(&WorldDefinition::from(world)).map_err(|e| e.to_string())?
+        } else {
+            serde_json::to_string_pretty(&WorldDefinition::from(world))
+                .map_err(|e| e.to_string())?
+        };
+
+        match &self.output {
+            Some(path) => std::fs::write(path, contents)
+                .map_err(|e| format!("failed to write {}: {e}", path.display())),
+            None => {
+                println!("{contents}");
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn species(id: JokeymonSpeciesId, diet: Diet) -> SpeciesDefinition {
+        SpeciesDefinition {
+            id,
+            avg_weight: 10,
+            avg_daily_food_consumption: 10,
+            diet,
+            evolves_to: None,
+        }
+    }
+
+    fn region(
+        id: RegionId,
+        demographics: &[(JokeymonSpeciesId, JokeymonCount)],
+    ) -> RegionDefinition {
+        RegionDefinition {
+            id,
+            latitude: 0,
+            longitude: id,
+            energy_yield: 10_000,
+            adjacent: vec![],
+            total_population: None,
+            demographics: demographics
+                .iter()
+                .map(|(species, count)| Population {
+                    species: *species,
+                    count: *count,
+                })
+                .collect(),
+        }
+    }
+
+    /// Two herbivores and a carnivore in two regions next to each other
+    fn world() -> WorldDefinition {
+        let mut first = region(0, &[(0, 100), (1, 50), (2, 5)]);
+        first.adjacent = vec![1];
+        WorldDefinition {
+            species: vec![
+                species(0, Diet::Herbivore),
+                species(1, Diet::Herbivore),
+                species(2, Diet::Carnivore),
+            ],
+            regions: vec![first, region(1, &[(0, 20)])],
+        }
+    }
+
+    fn error(world: WorldDefinition) -> String {
+        world.validate().expect_err("world should be rejected")
+    }
+
+    #[test]
+    fn valid_world_passes() {
+        assert_eq!(world().validate(), Ok(()));
+    }
+
+    #[test]
+    fn example_world_is_valid() {
+        let definition: WorldDefinition =
+            toml::from_str(include_str!("../../worlds/example.toml")).unwrap();
+        let world = definition.into_world().unwrap();
+        assert!(!world.regions.is_empty());
+        assert!(!world.species.is_empty());
+    }
+
+    #[test]
+    fn rejects_duplicate_ids() {
+        let mut definition = world();
+        definition.species.push(species(1, Diet::Carnivore));
+        assert_eq!(error(definition), "species 1 is defined twice");
+
+        let mut definition = world();
+        definition.regions.push(region(0, &[]));
+        assert_eq!(error(definition), "region 0 is defined twice");
+    }
+
+    #[test]
+    fn rejects_unknown_species() {
+        let mut definition = world();
+        definition.species[0].evolves_to = Some(9);
+        assert_eq!(error(definition), "species 0 evolves to unknown species 9");
+
+        let mut definition = world();
+        definition.regions[1] = region(1, &[(9, 1)]);
+        assert_eq!(error(definition), "region 1 has unknown species 9");
+    }
+
+    #[test]
+    fn rejects_evolution_cycles() {
+        let mut definition = world();
+        definition.species[0].evolves_to = Some(1);
+        definition.species[1].evolves_to = Some(0);
+        assert_eq!(error(definition), "species 0 is part of an evolution cycle");
+    }
+
+    #[test]
+    fn rejects_too_many_species_in_a_region() {
+        let max_species = <Runtime as pallet_omni::Config>::MaxSpeciesInRegion::get();
+        let mut definition = world();
+        definition.species = (0..=max_species)
+            .map(|id| species(id, Diet::Herbivore))
+            .collect();
+        let crowded: Vec<_> = (0..=max_species).map(|id| (id, 1)).collect();
+        definition.regions[1] = region(1, &crowded);
+        assert_eq!(
+            error(definition),
+            format!(
+                "region 1 has {} species, at most {max_species} are allowed",
+                max_species + 1
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_total_population() {
+        let mut definition = world();
+        definition.regions[0].total_population = Some(154);
+        assert_eq!(
+            error(definition),
+            "region 0 has a total population of 154 but its demographics add up to 155"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_adjacency() {
+        let mut definition = world();
+        definition.regions[1].adjacent = vec![1];
+        assert_eq!(error(definition), "region 1 is adjacent to itself");
+
+        let mut definition = world();
+        definition.regions[1].adjacent = vec![7];
+        assert_eq!(
+            error(definition),
+            "region 1 is adjacent to unknown region 7"
+        );
+    }
+}
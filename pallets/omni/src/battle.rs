@@ -0,0 +1,119 @@
+//! Deterministic battle resolution between two teams of jokeymon
+//!
+//! Battles are pure functions of the fighters and a stream of rolls, so the same teams and
+//! the same shared randomness always give the same result.
+
+use crate::types::{Diet, JokeymonSpeciesData};
+use sp_runtime::Vec;
+
+/// Experience needed for each percent of bonus health and attack
+pub const EXPERIENCE_PER_PERCENT: u32 = 10;
+/// Largest bonus experience can give, in percent
+pub const MAX_EXPERIENCE_BONUS: u32 = 50;
+
+/// The battle stats of a single jokeymon
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Fighter {
+    pub health: u32,
+    pub attack: u32,
+}
+
+impl Fighter {
+    /// Derive battle stats from species data
+    ///
+    /// Heavier jokeymon take more hits, hungrier ones hit harder and carnivores hit harder still
+    pub fn from_species(data: &JokeymonSpeciesData) -> Self {
+        let health = (data.avg_weight as u32).saturating_mul(10).max(1);
+        let base_attack = (data.avg_daily_food_consumption as u32).max(1);
+        let attack = match data.diet {
+            Diet::Herbivore => base_attack,
+            Diet::Carnivore => base_attack.saturating_mul(3) / 2,
+        };
+        Fighter { health, attack }
+    }
+
+    /// Raise health and attack by a percent for every [`EXPERIENCE_PER_PERCENT`] experience,
+    /// up to [`MAX_EXPERIENCE_BONUS`] percent
+    pub fn with_experience(self, experience: u32) -> Self {
+        let percent = 100 + (experience / EXPERIENCE_PER_PERCENT).min(MAX_EXPERIENCE_BONUS);
+        Fighter {
+            health: self.health.saturating_mul(percent) / 100,
+            attack: self.attack.saturating_mul(percent) / 100,
+        }
+    }
+}
+
+/// Who won a battle
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BattleOutcome {
+    FirstWins,
+    SecondWins,
+    Draw,
+}
+
+/// The result of a resolved battle
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BattleResult {
+    pub outcome: BattleOutcome,
+    pub rounds: u32,
+}
+
+/// Damage dealt by an attacker given a roll, between 80% and 120% of its attack
+fn damage(attacker: &Fighter, roll: u8) -> u32 {
+    let percent = 80 + (roll as u32 % 41);
+    attacker.attack.saturating_mul(percent) / 100
+}
+
+/// Fight two teams front to back until one runs out of jokeymon or `max_rounds` pass
+///
+/// Every round the front jokeymon of each team hit each other at the same time, fainted
+/// jokeymon are replaced by the next in line. `roll` is called with a distinct index for
+/// every attack. If both teams are still standing after `max_rounds` the team with the most
+/// health left wins.
+pub fn resolve(
+    mut first: Vec<Fighter>,
+    mut second: Vec<Fighter>,
+    max_rounds: u32,
+    mut roll: impl FnMut(u32) -> u8,
+) -> BattleResult {
+    first.reverse();
+    second.reverse();
+
+    let mut rounds = 0;
+    while rounds < max_rounds {
+        let (Some(a), Some(b)) = (first.last(), second.last()) else {
+            break;
+        };
+        let to_b = damage(a, roll(rounds.saturating_mul(2)));
+        let to_a = damage(b, roll(rounds.saturating_mul(2).saturating_add(1)));
+        rounds += 1;
+
+        if let Some(a) = first.last_mut() {
+            a.health = a.health.saturating_sub(to_a);
+            if a.health == 0 {
+                first.pop();
+            }
+        }
+        if let Some(b) = second.last_mut() {
+            b.health = b.health.saturating_sub(to_b);
+            if b.health == 0 {
+                second.pop();
+            }
+        }
+    }
+
+    let outcome = match (first.is_empty(), second.is_empty()) {
+        (true, true) => BattleOutcome::Draw,
+        (false, true) => BattleOutcome::FirstWins,
+        (true, false) => BattleOutcome::SecondWins,
+        (false, false) => {
+            let health = |team: &Vec<Fighter>| team.iter().map(|f| f.health as u64).sum::<u64>();
+            match health(&first).cmp(&health(&second)) {
+                core::cmp::Ordering::Greater => BattleOutcome::FirstWins,
+                core::cmp::Ordering::Less => BattleOutcome::SecondWins,
+                core::cmp::Ordering::Equal => BattleOutcome::Draw,
+            }
+        }
+    };
+    BattleResult { outcome, rounds }
+}
@@ -0,0 +1,104 @@
+//! Signed extension throttling catches
+//!
+//! [`CheckCatchStamina`] rejects `catch_jokeymon` from accounts without catch stamina while the
+//! transaction is validated, so over the limit catches are dropped from the pool instead of
+//! being included and failing.
+//!
+//! A catch provides a tag made of the account and its stamina, so the pool holds at most one
+//! catch of an account until that catch is in a block and its stamina is spent. A catch from an
+//! account whose stamina regenerates is reported as valid in the future rather than rejected.
+
+use crate::{Call, Config, Pallet};
+use codec::{Decode, Encode};
+use core::marker::PhantomData;
+use frame_support::traits::{Get, IsSubType};
+use scale_info::TypeInfo;
+use sp_runtime::{
+    traits::{DispatchInfoOf, SaturatedConversion, SignedExtension, Zero},
+    transaction_validity::{
+        InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+    },
+};
+
+/// `InvalidTransaction::Custom` code of a catch from an account whose stamina never regenerates
+pub const OUT_OF_STAMINA: u8 = 1;
+
+/// Reject catches from accounts that are out of catch stamina
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckCatchStamina<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckCatchStamina<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Config + Send + Sync> Default for CheckCatchStamina<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config + Send + Sync> core::fmt::Debug for CheckCatchStamina<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "CheckCatchStamina")
+    }
+}
+
+impl<T: Config + Send + Sync> SignedExtension for CheckCatchStamina<T>
+where
+    T::RuntimeCall: IsSubType<Call<T>>,
+{
+    const IDENTIFIER: &'static str = "CheckCatchStamina";
+    type AccountId = T::AccountId;
+    type Call = T::RuntimeCall;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> TransactionValidity {
+        let Some(Call::catch_jokeymon {}) = call.is_sub_type() else {
+            return Ok(ValidTransaction::default());
+        };
+        let regen = T::CatchStaminaRegen::get();
+        let points = Pallet::<T>::catch_stamina(who).points;
+        if points == 0 {
+            // stamina that comes back makes the catch valid later
+            return Err(if regen.is_zero() {
+                InvalidTransaction::Custom(OUT_OF_STAMINA)
+            } else {
+                InvalidTransaction::Future
+            }
+            .into());
+        }
+
+        let mut valid = ValidTransaction::with_tag_prefix(Self::IDENTIFIER)
+            .and_provides((who, points))
+            .build()?;
+        // a regenerated point changes the tag, so the catch is checked again
+        if !regen.is_zero() {
+            valid.longevity = regen.saturated_into();
+        }
+        Ok(valid)
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<(), TransactionValidityError> {
+        self.validate(who, call, info, len).map(|_| ())
+    }
+}
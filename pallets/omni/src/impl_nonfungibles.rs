@@ -0,0 +1,111 @@
+//! Implementations of the nonfungibles traits for the omni pallet
+//!
+//! Every jokeymon is an item of the [`JOKEYMON_COLLECTION`] collection, its species and stats
+//! are exposed as attributes under the keys below.
+
+use crate::{types::*, *};
+use codec::Encode;
+use frame_support::{
+    ensure,
+    storage::with_storage_layer,
+    traits::tokens::nonfungibles_v2::{Inspect, Transfer},
+};
+use sp_runtime::{DispatchError, DispatchResult, Vec};
+
+/// Attribute key of the jokeymon species id
+pub const SPECIES_KEY: &[u8] = b"species";
+/// Attribute key of the jokeymon birth date
+pub const BIRTH_DATE_KEY: &[u8] = b"birth_date";
+/// Attribute key of the species average weight
+pub const AVG_WEIGHT_KEY: &[u8] = b"avg_weight";
+/// Attribute key of the species average daily food consumption
+pub const AVG_DAILY_FOOD_CONSUMPTION_KEY: &[u8] = b"avg_daily_food_consumption";
+/// Attribute key of the species diet
+pub const DIET_KEY: &[u8] = b"diet";
+/// Attribute key of the species the jokeymon evolves to
+pub const EVOLVES_TO_KEY: &[u8] = b"evolves_to";
+
+impl<T: Config> Inspect<T::AccountId> for Pallet<T> {
+    type ItemId = JokeymonId;
+    type CollectionId = JokeymonCollectionId;
+
+    fn owner(collection: &Self::CollectionId, item: &Self::ItemId) -> Option<T::AccountId> {
+        if *collection != JOKEYMON_COLLECTION {
+            return None;
+        }
+        JokeymonIdToOwner::<T>::get(item)
+    }
+
+    fn attribute(
+        collection: &Self::CollectionId,
+        item: &Self::ItemId,
+        key: &[u8],
+    ) -> Option<Vec<u8>> {
+        if *collection != JOKEYMON_COLLECTION {
+            return None;
+        }
+        let data = JokeymonIdToData::<T>::get(item)?;
+        let species = SpeciesIdToSpeciesData::<T>::get(data.id);
+        match key {
+            SPECIES_KEY => Some(data.id.encode()),
+            BIRTH_DATE_KEY => Some(data.birth_date.encode()),
+            AVG_WEIGHT_KEY => Some(species.avg_weight.encode()),
+            AVG_DAILY_FOOD_CONSUMPTION_KEY => Some(species.avg_daily_food_consumption.encode()),
+            DIET_KEY => Some(species.diet.encode()),
+            EVOLVES_TO_KEY => Some(species.evolves_to.encode()),
+            _ => None,
+        }
+    }
+
+    fn can_transfer(collection: &Self::CollectionId, item: &Self::ItemId) -> bool {
+        *collection == JOKEYMON_COLLECTION
+            && JokeymonIdToOwner::<T>::contains_key(item)
+            && !LockedJokeymon::<T>::contains_key(item)
+    }
+}
+
+impl<T: Config> Transfer<T::AccountId> for Pallet<T> {
+    fn transfer(
+        collection: &Self::CollectionId,
+        item: &Self::ItemId,
+        destination: &T::AccountId,
+    ) -> DispatchResult {
+        ensure_collection(collection)?;
+        with_storage_layer(|| Self::do_transfer(*item, destination))
+    }
+
+    /// Lock a jokeymon, fails if the pallet holds a lock of its own on it
+    fn disable_transfer(collection: &Self::CollectionId, item: &Self::ItemId) -> DispatchResult {
+        ensure_collection(collection)?;
+        ensure!(
+            JokeymonIdToOwner::<T>::contains_key(item),
+            Error::<T>::UnknownJokeymon
+        );
+        ensure_not_locked_by_pallet::<T>(item)?;
+        Self::lock_jokeymon(*item, LockReason::Transfer);
+        Ok(())
+    }
+
+    /// Lift a lock taken with `disable_transfer`, fails if the jokeymon is listed, auctioned,
+    /// wagered or defending a gym
+    fn enable_transfer(collection: &Self::CollectionId, item: &Self::ItemId) -> DispatchResult {
+        ensure_collection(collection)?;
+        ensure_not_locked_by_pallet::<T>(item)?;
+        Self::unlock_jokeymon(*item, LockReason::Transfer);
+        Ok(())
+    }
+}
+
+fn ensure_not_locked_by_pallet<T: Config>(item: &JokeymonId) -> DispatchResult {
+    match LockedJokeymon::<T>::get(item) {
+        Some(reason) if reason != LockReason::Transfer => Err(Error::<T>::JokeymonLocked.into()),
+        _ => Ok(()),
+    }
+}
+
+fn ensure_collection(collection: &JokeymonCollectionId) -> Result<(), DispatchError> {
+    if *collection != JOKEYMON_COLLECTION {
+        return Err(sp_runtime::TokenError::UnknownAsset.into());
+    }
+    Ok(())
+}
@@ -25,33 +25,56 @@
 //   Update players food resources & jokeymon
 
 // Next
-// inherent 
+// inherent
 // offchain function
-// use try-runtime for something
 
 pub use pallet::*;
-pub mod types;
+pub mod battle;
+pub mod extension;
+pub mod impl_nonfungibles;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
+pub mod pokedex;
+pub mod rating;
+pub mod runtime_api;
 #[cfg(test)]
 mod tests;
+pub mod types;
 pub mod weights;
+pub mod worldgen;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+// the call macro converts every `DispatchResultWithPostInfo` into itself, and the hooks macro
+// logs a failed `try_state` with `map_err`
+#[allow(clippy::useless_conversion)]
+#[cfg_attr(feature = "try-runtime", allow(clippy::manual_inspect))]
 #[frame_support::pallet]
 pub mod pallet {
-    use crate::types::*;
+    use crate::{
+        battle::{self, BattleOutcome, Fighter},
+        rating::{self, Score, INITIAL_RATING},
+        types::*,
+    };
     use frame_support::{
         dispatch::DispatchResultWithPostInfo,
         pallet_prelude::*,
-        traits::{BuildGenesisConfig, Randomness},
-        Blake2_128Concat, BoundedBTreeMap,
+        storage::with_storage_layer,
+        traits::{
+            fungible::{self, Mutate, MutateHold},
+            tokens::{Fortitude, Precision, Preservation, Restriction},
+            BuildGenesisConfig, Randomness,
+        },
+        Blake2_128Concat, BoundedBTreeMap, PalletId,
     };
     use frame_system::{pallet_prelude::*, Pallet as SystemPallet};
-    use scale_info::prelude::{collections::BTreeMap, vec};
-    use sp_runtime::{traits::Saturating, Permill, Vec};
+    use scale_info::prelude::{collections::BTreeMap, format, string::String};
+    use sp_runtime::{
+        traits::{AccountIdConversion, Hash, SaturatedConversion, Saturating, Zero},
+        Perbill, Permill, Vec,
+    };
 
     /// Genesis Storage
     #[pallet::genesis_config]
@@ -72,6 +95,10 @@ pub mod pallet {
     #[pallet::genesis_build]
     impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
         fn build(&self) {
+            if let Err(e) = self.check() {
+                panic!("genesis {}", e);
+            }
+
             // Regions
             for (a, b) in &self.region_id_to_region {
                 RegionIdToRegion::<T>::insert(a, b);
@@ -80,6 +107,86 @@ pub mod pallet {
             for (a, b) in &self.species_id_to_data {
                 SpeciesIdToSpeciesData::<T>::insert(a, b);
             }
+            // New regions get ids past every genesis region
+            if let Some(last) = self.region_id_to_region.iter().map(|(id, _)| *id).max() {
+                RegionNonce::<T>::put(last.saturating_add(1));
+            }
+        }
+    }
+
+    impl<T: Config> GenesisConfig<T> {
+        /// Check the regions and species are consistent with each other
+        ///
+        /// - every id matches its key and no key is repeated
+        /// - every region's total population is the sum of its demographics
+        /// - every species in a region and every `evolves_to` target exists
+        /// - no species evolves back into itself
+        /// - every herbivore eats something
+        /// - no population is above `MAX_SPECIES_POPULATION`
+        ///
+        /// The node checks world definitions with this too, so a world it accepts always builds.
+        pub fn check(&self) -> Result<(), String> {
+            let mut species = BTreeMap::new();
+            for (key, data) in &self.species_id_to_data {
+                if *key != data.id {
+                    return Err(format!("species {} is stored under id {}", data.id, key));
+                }
+                if species.insert(*key, data.evolves_to).is_some() {
+                    return Err(format!("species {} is defined twice", key));
+                }
+                if !data.has_food_intake() {
+                    return Err(format!("species {} is a herbivore that eats nothing", key));
+                }
+            }
+            for (id, evolves_to) in &species {
+                if let Some(target) = evolves_to {
+                    if !species.contains_key(target) {
+                        return Err(format!(
+                            "species {} evolves to unknown species {}",
+                            id, target
+                        ));
+                    }
+                }
+                // a chain longer than the number of species has to revisit one
+                let mut next = *evolves_to;
+                for _ in 0..species.len() {
+                    let Some(current) = next else { break };
+                    if current == *id {
+                        return Err(format!("species {} is part of an evolution cycle", id));
+                    }
+                    next = species.get(&current).copied().flatten();
+                }
+            }
+
+            let mut regions = BTreeMap::new();
+            for (key, region) in &self.region_id_to_region {
+                if *key != region.id {
+                    return Err(format!("region {} is stored under id {}", region.id, key));
+                }
+                if regions.insert(*key, ()).is_some() {
+                    return Err(format!("region {} is defined twice", key));
+                }
+                let mut total = 0u64;
+                for (species_id, count) in &region.population_demographics {
+                    if !species.contains_key(species_id) {
+                        return Err(format!("region {} has unknown species {}", key, species_id));
+                    }
+                    if *count > MAX_SPECIES_POPULATION {
+                        return Err(format!(
+                            "region {} has {} of species {}, at most {} are allowed",
+                            key, count, species_id, MAX_SPECIES_POPULATION
+                        ));
+                    }
+                    total += *count as u64;
+                }
+                if region.total_population != total {
+                    return Err(format!(
+                        "region {} has a total population of {} but its demographics add up to {}",
+                        key, region.total_population, total
+                    ));
+                }
+            }
+            Ok(())
         }
     }
 
@@ -100,9 +207,141 @@ pub mod pallet {
 
         /// Maximum jokeymon an account can hold at a time
         type MaxJokeymonHoldable: Get<u32>;
+
+        /// The currency fees and deposits are paid in
+        type Currency: fungible::Mutate<Self::AccountId>
+            + fungible::MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+
+        /// The overarching hold reason
+        type RuntimeHoldReason: From<HoldReason>;
+
+        /// The pallet id, used to derive the pot account fees are paid into
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+
+        /// Fee paid into the pot for every catch attempt
+        #[pallet::constant]
+        type CatchFee: Get<BalanceOf<Self>>;
+
+        /// Fee paid into the pot for travelling to another region
+        #[pallet::constant]
+        type TravelFee: Get<BalanceOf<Self>>;
+
+        /// Deposit held for every jokeymon an account owns, returned on release
+        #[pallet::constant]
+        type JokeymonDeposit: Get<BalanceOf<Self>>;
+
+        /// Cut of every marketplace sale paid to the treasury
+        #[pallet::constant]
+        type MarketplaceFee: Get<Permill>;
+
+        /// The account marketplace fees are paid to
+        type TreasuryAccount: Get<Self::AccountId>;
+
+        /// Maximum number of auctions running at a time
+        #[pallet::constant]
+        type MaxActiveAuctions: Get<u32>;
+
+        /// Maximum length of an auction in blocks
+        #[pallet::constant]
+        type MaxAuctionDuration: Get<BlockNumberFor<Self>>;
+
+        /// Maximum number of jokeymon in a battle team
+        #[pallet::constant]
+        type MaxTeamSize: Get<u32>;
+
+        /// Maximum number of rounds in a battle before it is decided on remaining health
+        #[pallet::constant]
+        type MaxBattleRounds: Get<u32>;
+
+        /// Length of a rated season in blocks
+        #[pallet::constant]
+        type SeasonLength: Get<BlockNumberFor<Self>>;
+
+        /// Number of accounts kept on the leaderboard and rewarded at the end of a season
+        #[pallet::constant]
+        type LeaderboardSize: Get<u32>;
+
+        /// Total reward paid from the pot to the leaderboard at the end of a season
+        #[pallet::constant]
+        type SeasonReward: Get<BalanceOf<Self>>;
+
+        /// Number of species ids tracked in an account's pokedex
+        #[pallet::constant]
+        type MaxSpecies: Get<u32>;
+
+        /// Number of distinct species caught between pokedex rewards
+        #[pallet::constant]
+        type PokedexMilestone: Get<u32>;
+
+        /// Reward paid from the pot every time a pokedex milestone is reached
+        #[pallet::constant]
+        type PokedexReward: Get<BalanceOf<Self>>;
+
+        /// Origin allowed to manage game content such as quests
+        type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Maximum number of quests open at a time
+        #[pallet::constant]
+        type MaxQuests: Get<u32>;
+
+        /// Reward paid from the pot to a gym controller for every block they hold it
+        #[pallet::constant]
+        type GymReward: Get<BalanceOf<Self>>;
+
+        /// Maximum number of members in a guild
+        #[pallet::constant]
+        type MaxGuildMembers: Get<u32>;
+
+        /// Blocks between population snapshots, zero stops sampling
+        #[pallet::constant]
+        type PopulationSampleInterval: Get<BlockNumberFor<Self>>;
+
+        /// Number of population snapshots kept per region, older ones are dropped
+        #[pallet::constant]
+        type PopulationHistoryLength: Get<u32>;
+
+        /// Regions sampled in a block, the rest of a sample are taken in the blocks after
+        #[pallet::constant]
+        type MaxRegionsSampledPerBlock: Get<u32>;
+
+        /// Share of its carrying capacity below which a species can't be caught in a region
+        #[pallet::constant]
+        type ProtectionThreshold: Get<Permill>;
+
+        /// Catches an account can make in a row
+        #[pallet::constant]
+        type MaxCatchStamina: Get<u32>;
+
+        /// Blocks it takes an account to regain one catch, zero stops throttling
+        #[pallet::constant]
+        type CatchStaminaRegen: Get<BlockNumberFor<Self>>;
+
+        /// Blocks two accounts have to wait between rated matches against each other
+        #[pallet::constant]
+        type RatedMatchCooldown: Get<BlockNumberFor<Self>>;
+
+        /// Experience shared across the party of the winner of a battle
+        #[pallet::constant]
+        type BattleExperience: Get<u32>;
+    }
+
+    /// Reasons the pallet holds funds
+    #[pallet::composite_enum]
+    pub enum HoldReason {
+        /// Storage deposit for an owned jokeymon
+        JokeymonDeposit,
+        /// Bid on a running auction
+        AuctionBid,
+        /// Wager staked on a battle
+        BattleWager,
     }
 
+    /// The in-code storage version
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     /// The seed for randomness
@@ -132,11 +371,195 @@ pub mod pallet {
     pub type JokeymonIdToData<T: Config> =
         StorageMap<_, Blake2_128Concat, JokeymonId, JokeymonData<T>, OptionQuery>;
 
+    /// Jokeymon unique id to its owner
+    #[pallet::storage]
+    pub type JokeymonIdToOwner<T: Config> =
+        StorageMap<_, Blake2_128Concat, JokeymonId, T::AccountId, OptionQuery>;
+
+    /// Experience a jokeymon has gained from battles its owner's party won
+    #[pallet::storage]
+    pub type Experience<T: Config> = StorageMap<_, Blake2_128Concat, JokeymonId, u32, ValueQuery>;
+
+    /// Jokeymon that currently cannot be transferred or released, and what locked them
+    #[pallet::storage]
+    pub type LockedJokeymon<T: Config> =
+        StorageMap<_, Blake2_128Concat, JokeymonId, LockReason, OptionQuery>;
+
+    /// Jokeymon listed for sale on the marketplace
+    #[pallet::storage]
+    pub type Listings<T: Config> =
+        StorageMap<_, Blake2_128Concat, JokeymonId, Listing<T>, OptionQuery>;
+
+    /// Jokeymon currently up for auction
+    #[pallet::storage]
+    pub type Auctions<T: Config> =
+        StorageMap<_, Blake2_128Concat, JokeymonId, Auction<T>, OptionQuery>;
+
+    /// Running auctions ordered by the block they end at
+    #[pallet::storage]
+    pub type AuctionQueue<T: Config> = StorageValue<
+        _,
+        BoundedVec<(BlockNumberFor<T>, JokeymonId), T::MaxActiveAuctions>,
+        ValueQuery,
+    >;
+
+    /// Challenged account to the challenge awaiting its answer
+    #[pallet::storage]
+    pub type Challenges<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Challenge<T>, OptionQuery>;
+
+    /// Account to its battle results
+    #[pallet::storage]
+    pub type BattleRecords<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BattleRecord, ValueQuery>;
+
+    #[pallet::type_value]
+    pub fn DefaultRating() -> Rating {
+        INITIAL_RATING
+    }
+
+    /// The current rated season
+    #[pallet::storage]
+    pub type CurrentSeason<T: Config> = StorageValue<_, SeasonIndex, ValueQuery>;
+
+    /// Season and account to the account's rating that season
+    #[pallet::storage]
+    pub type Ratings<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        SeasonIndex,
+        Blake2_128Concat,
+        T::AccountId,
+        Rating,
+        ValueQuery,
+        DefaultRating,
+    >;
+
+    /// Accounts that accept rated matches against anyone else on the ladder
+    #[pallet::storage]
+    pub type LadderPlayers<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    /// Pair of accounts, lowest first, to the block they last played a rated match
+    #[pallet::storage]
+    pub type LastRatedMatch<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::AccountId,
+        BlockNumberFor<T>,
+        OptionQuery,
+    >;
+
+    /// The top rated accounts of the current season, kept up to date as matches are played
+    ///
+    /// An account that drops off is only put back when it plays again. The board is what the
+    /// season ends with, so the season end never has to read every rating.
+    #[pallet::storage]
+    pub type Leaderboard<T: Config> = StorageValue<_, Standings<T>, ValueQuery>;
+
+    /// Final leaderboard of every finished season
+    #[pallet::storage]
+    pub type SeasonStandings<T: Config> =
+        StorageMap<_, Blake2_128Concat, SeasonIndex, Standings<T>, OptionQuery>;
+
+    /// Account to the species it has seen and caught
+    #[pallet::storage]
+    pub type Pokedexes<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Pokedex<T>, ValueQuery>;
+
+    /// nonce for quest ids
+    #[pallet::storage]
+    pub type QuestNonce<T: Config> = StorageValue<_, QuestId, ValueQuery>;
+
+    /// Quest id to an open quest
+    #[pallet::storage]
+    pub type Quests<T: Config> = StorageMap<_, Blake2_128Concat, QuestId, Quest<T>, OptionQuery>;
+
+    /// Ids of every open quest, checked on each catch
+    #[pallet::storage]
+    pub type OpenQuests<T: Config> = StorageValue<_, BoundedVec<QuestId, T::MaxQuests>, ValueQuery>;
+
+    /// Account and quest id to the account's progress on the quest
+    #[pallet::storage]
+    pub type QuestProgresses<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        QuestId,
+        QuestProgress,
+        ValueQuery,
+    >;
+
+    /// Region id to the gym it hosts
+    #[pallet::storage]
+    pub type Gyms<T: Config> = StorageMap<_, Blake2_128Concat, RegionId, Gym<T>, OptionQuery>;
+
+    /// nonce for guild ids
+    #[pallet::storage]
+    pub type GuildNonce<T: Config> = StorageValue<_, GuildId, ValueQuery>;
+
+    /// Guild id to the guild
+    #[pallet::storage]
+    pub type Guilds<T: Config> = StorageMap<_, Blake2_128Concat, GuildId, Guild, OptionQuery>;
+
+    /// Account to its guild membership
+    #[pallet::storage]
+    pub type GuildMembers<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, GuildMember, OptionQuery>;
+
+    /// Roster jokeymon to the member borrowing it
+    #[pallet::storage]
+    pub type BorrowedJokeymon<T: Config> =
+        StorageMap<_, Blake2_128Concat, JokeymonId, T::AccountId, OptionQuery>;
+
     /// Species id to general species data
     #[pallet::storage]
     pub type SpeciesIdToSpeciesData<T: Config> =
         StorageMap<_, Blake2_128Concat, JokeymonSpeciesId, JokeymonSpeciesData, ValueQuery>;
 
+    /// Catch allowance of accounts that caught recently, missing means full
+    #[pallet::storage]
+    pub type Stamina<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, CatchStamina<T>, OptionQuery>;
+
+    /// Species that can't be caught in a region until their population recovers
+    #[pallet::storage]
+    pub type ProtectedSpecies<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        RegionId,
+        Blake2_128Concat,
+        JokeymonSpeciesId,
+        (),
+        OptionQuery,
+    >;
+
+    /// Region and slot to a population snapshot, each region's slots are a ring buffer of
+    /// `PopulationHistoryLength` snapshots
+    #[pallet::storage]
+    pub type PopulationHistory<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        RegionId,
+        Twox64Concat,
+        u32,
+        PopulationSnapshot<T>,
+        OptionQuery,
+    >;
+
+    /// Region to the slot its next snapshot is written to, which holds its oldest snapshot
+    #[pallet::storage]
+    pub type PopulationHistoryHead<T: Config> =
+        StorageMap<_, Blake2_128Concat, RegionId, u32, ValueQuery>;
+
+    /// The last region sampled, while a sample is still being taken
+    #[pallet::storage]
+    pub type PopulationSampleCursor<T: Config> = StorageValue<_, RegionId, OptionQuery>;
+
+    /// What each event means for storage is documented in `EVENTS.md`
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -146,28 +569,381 @@ pub mod pallet {
             jokeymon_id: JokeymonId,
             who: T::AccountId,
         },
+        /// A jokeymon was released back into a region
+        JokeymonReleased {
+            species_id: JokeymonSpeciesId,
+            jokeymon_id: JokeymonId,
+            region_id: RegionId,
+            who: T::AccountId,
+        },
+        /// An account travelled to a new region
+        Travelled {
+            who: T::AccountId,
+            from: RegionId,
+            to: RegionId,
+        },
+        /// A jokeymon changed owner
+        JokeymonTransferred {
+            jokeymon_id: JokeymonId,
+            from: T::AccountId,
+            to: T::AccountId,
+        },
+        /// A jokeymon was listed for sale
+        JokeymonListed {
+            jokeymon_id: JokeymonId,
+            seller: T::AccountId,
+            price: BalanceOf<T>,
+        },
+        /// A listing was cancelled by the seller
+        ListingCancelled {
+            jokeymon_id: JokeymonId,
+            seller: T::AccountId,
+        },
+        /// A listed jokeymon was bought
+        JokeymonSold {
+            jokeymon_id: JokeymonId,
+            seller: T::AccountId,
+            buyer: T::AccountId,
+            price: BalanceOf<T>,
+            fee: BalanceOf<T>,
+        },
+        /// A jokeymon was put up for auction
+        AuctionCreated {
+            jokeymon_id: JokeymonId,
+            seller: T::AccountId,
+            kind: AuctionKind<BalanceOf<T>>,
+            end: BlockNumberFor<T>,
+        },
+        /// A bid was placed on an english auction
+        BidPlaced {
+            jokeymon_id: JokeymonId,
+            bidder: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// An outbid bidder got their bid back
+        BidRefunded {
+            jokeymon_id: JokeymonId,
+            bidder: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// An auction ended with a sale
+        AuctionSettled {
+            jokeymon_id: JokeymonId,
+            seller: T::AccountId,
+            buyer: T::AccountId,
+            price: BalanceOf<T>,
+            fee: BalanceOf<T>,
+        },
+        /// An auction ended without a sale, the jokeymon stays with the seller
+        AuctionUnsold {
+            jokeymon_id: JokeymonId,
+            seller: T::AccountId,
+        },
+        /// An account was challenged to a battle
+        ChallengeIssued {
+            challenger: T::AccountId,
+            opponent: T::AccountId,
+            wager: BalanceOf<T>,
+        },
+        /// A challenge was withdrawn or declined
+        ChallengeCancelled {
+            challenger: T::AccountId,
+            opponent: T::AccountId,
+        },
+        /// A battle was fought, `winner` is `None` on a draw
+        BattleResolved {
+            challenger: T::AccountId,
+            opponent: T::AccountId,
+            winner: Option<T::AccountId>,
+            rounds: u32,
+            wager: BalanceOf<T>,
+        },
+        /// A rated match was played, `winner` is `None` on a draw
+        RatedMatchPlayed {
+            player: T::AccountId,
+            opponent: T::AccountId,
+            winner: Option<T::AccountId>,
+            player_rating: Rating,
+            opponent_rating: Rating,
+        },
+        /// A season ended and its leaderboard was stored
+        SeasonEnded { season: SeasonIndex },
+        /// A leaderboard account was paid its season reward
+        SeasonRewardPaid {
+            season: SeasonIndex,
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// An account caught enough distinct species to reach a pokedex milestone
+        ///
+        /// `reward` is zero if the pot couldn't pay it
+        PokedexMilestoneReached {
+            who: T::AccountId,
+            caught: u32,
+            reward: BalanceOf<T>,
+        },
+        /// A quest was opened
+        QuestCreated { quest_id: QuestId },
+        /// A quest was closed, unclaimed rewards are forfeit
+        QuestRemoved { quest_id: QuestId },
+        /// An account met a quest's objective
+        QuestCompleted {
+            who: T::AccountId,
+            quest_id: QuestId,
+        },
+        /// An account claimed the reward of a completed quest
+        QuestRewardClaimed {
+            who: T::AccountId,
+            quest_id: QuestId,
+            reward: QuestReward<BalanceOf<T>>,
+        },
+        /// A region started hosting a gym
+        GymCreated { region_id: RegionId },
+        /// A region stopped hosting a gym
+        GymRemoved { region_id: RegionId },
+        /// An account took control of a gym
+        GymCaptured {
+            region_id: RegionId,
+            who: T::AccountId,
+            previous: Option<T::AccountId>,
+        },
+        /// A gym's defending team beat a challenger
+        GymDefended {
+            region_id: RegionId,
+            controller: T::AccountId,
+            challenger: T::AccountId,
+        },
+        /// A controller gave up their gym
+        GymAbandoned {
+            region_id: RegionId,
+            who: T::AccountId,
+        },
+        /// A guild was founded
+        GuildCreated {
+            guild_id: GuildId,
+            founder: T::AccountId,
+        },
+        /// An account joined a guild
+        GuildJoined {
+            guild_id: GuildId,
+            who: T::AccountId,
+        },
+        /// An account left or was removed from a guild
+        GuildLeft {
+            guild_id: GuildId,
+            who: T::AccountId,
+        },
+        /// The last member left a guild
+        GuildDissolved { guild_id: GuildId },
+        /// A member's role changed
+        GuildRoleSet {
+            guild_id: GuildId,
+            who: T::AccountId,
+            role: GuildRole,
+        },
+        /// A member paid into the guild pot
+        GuildFunded {
+            guild_id: GuildId,
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// The leader spent from the guild pot
+        GuildFundsWithdrawn {
+            guild_id: GuildId,
+            dest: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// A member lent a roster jokeymon
+        JokeymonBorrowed {
+            guild_id: GuildId,
+            jokeymon_id: JokeymonId,
+            who: T::AccountId,
+        },
+        /// A borrowed jokeymon went back to the roster
+        JokeymonReturned {
+            guild_id: GuildId,
+            jokeymon_id: JokeymonId,
+            who: T::AccountId,
+        },
+        /// An account changed its party
+        PartySet { who: T::AccountId, party: Team<T> },
+        /// A gym controller was paid for the blocks they held it
+        GymRewardPaid {
+            region_id: RegionId,
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// The block's population update changed a region
+        ///
+        /// `changes` holds the change in count of every species whose count changed
+        RegionPopulationUpdated {
+            region_id: RegionId,
+            changes: BoundedVec<(JokeymonSpeciesId, i64), T::MaxSpeciesInRegion>,
+        },
+        /// A region was added to the world
+        RegionCreated {
+            region_id: RegionId,
+            latitude: Coordinate,
+            longitude: Coordinate,
+            energy_yield: u32,
+            population_demographics: RegionPopulationDemographics<T>,
+        },
+        /// A species was added or its data replaced
+        SpeciesSet { species: JokeymonSpeciesData },
+        /// A species fell below the protection threshold and can't be caught in the region
+        SpeciesProtected {
+            region_id: RegionId,
+            species_id: JokeymonSpeciesId,
+        },
+        /// A protected species recovered or left the region
+        SpeciesUnprotected {
+            region_id: RegionId,
+            species_id: JokeymonSpeciesId,
+        },
+        /// A jokeymon was minted as a quest reward
+        JokeymonRewarded {
+            species_id: JokeymonSpeciesId,
+            jokeymon_id: JokeymonId,
+            who: T::AccountId,
+        },
+        /// An account joined the rated ladder
+        LadderJoined { who: T::AccountId },
+        /// An account left the rated ladder
+        LadderLeft { who: T::AccountId },
+        /// An account won a battle and every jokeymon in its party gained `amount` experience
+        ExperienceShared {
+            who: T::AccountId,
+            party: Team<T>,
+            amount: u32,
+        },
     }
 
     #[cfg_attr(test, derive(PartialEq))]
     #[pallet::error]
     pub enum Error<T> {
-        /// No room left for jokeymon in the account's party
+        /// The account already holds `MaxJokeymonHoldable` jokeymon
         TooManyJokeymon,
-        /// No jokeymon left in the region to catch
+        /// No jokeymon left in the region to catch, or every species left is protected
         NoCatchableJokeymon,
         /// No room left in region for new species
         RegionSpeciesDiversitySaturated,
+        /// The region does not exist
+        UnknownRegion,
+        /// The account is already in the region
+        AlreadyInRegion,
+        /// The account does not own the jokeymon
+        NotJokeymonOwner,
+        /// The jokeymon does not exist
+        UnknownJokeymon,
+        /// The jokeymon is locked and cannot be moved
+        JokeymonLocked,
+        /// The jokeymon is already listed for sale
+        AlreadyListed,
+        /// The jokeymon is not listed for sale
+        NotListed,
+        /// The seller cannot buy their own jokeymon
+        CannotBuyOwnJokeymon,
+        /// The auction parameters are invalid
+        InvalidAuction,
+        /// No room left for new auctions
+        TooManyAuctions,
+        /// The jokeymon is not up for auction
+        AuctionNotFound,
+        /// The auction has already ended
+        AuctionEnded,
+        /// The bid is below the minimum or current price
+        BidTooLow,
+        /// The team is empty, has duplicates or contains jokeymon the account does not own
+        InvalidTeam,
+        /// An account cannot challenge itself
+        CannotChallengeSelf,
+        /// The opponent already has a challenge waiting
+        AlreadyChallenged,
+        /// There is no such challenge
+        ChallengeNotFound,
+        /// One of the accounts has no jokeymon to battle with
+        NoJokeymonToBattle,
+        /// The quest's objective can never be met
+        InvalidQuest,
+        /// No room left for new quests
+        TooManyQuests,
+        /// The quest does not exist
+        UnknownQuest,
+        /// The quest's objective has not been met yet
+        QuestNotComplete,
+        /// The quest's reward has already been claimed
+        QuestAlreadyClaimed,
+        /// The region already hosts a gym
+        GymAlreadyExists,
+        /// The region does not host a gym
+        GymNotFound,
+        /// The account has to be in the gym's region
+        NotInRegion,
+        /// The account does not control the gym
+        NotGymController,
+        /// The account already controls the gym
+        AlreadyGymController,
+        /// The account is already in a guild
+        AlreadyInGuild,
+        /// The account is not in the guild
+        NotInGuild,
+        /// The guild does not exist
+        UnknownGuild,
+        /// No room left for members in the guild
+        GuildFull,
+        /// The member's role does not allow this
+        InsufficientGuildRole,
+        /// The leader has to hand over the guild before leaving it
+        LeaderCannotLeave,
+        /// The guild still holds jokeymon in its roster
+        GuildRosterNotEmpty,
+        /// The member has to return borrowed jokeymon first
+        HasBorrowedJokeymon,
+        /// The jokeymon is not in the guild roster
+        NotInRoster,
+        /// The jokeymon is lent to a member
+        JokeymonBorrowed,
+        /// The jokeymon is not borrowed by the account
+        NotBorrowed,
+        /// The jokeymon is not in the account's party
+        NotInParty,
+        /// The jokeymon is already in the account's party
+        AlreadyInParty,
+        /// No room left in the account's party
+        PartyFull,
+        /// The species does not exist
+        UnknownSpecies,
+        /// The species would evolve back into itself
+        EvolutionCycle,
+        /// The account has to wait to regain stamina before catching again
+        OutOfStamina,
+        /// The account is already on the rated ladder
+        AlreadyOnLadder,
+        /// One of the accounts is not on the rated ladder
+        NotOnLadder,
+        /// The accounts played a rated match against each other too recently
+        RatedMatchTooSoon,
+        /// Herbivores have to have a daily food consumption
+        NoFoodConsumption,
+        /// A species population is above `MAX_SPECIES_POPULATION`
+        PopulationTooLarge,
     }
 
     #[pallet::hooks]
     impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
-            // For each region, update it's population
-            for (region_id, mut region) in RegionIdToRegion::<T>::iter() {
-                Self::update_regional_population(&mut region);
-                RegionIdToRegion::<T>::insert(region_id, region);
-            }
-            Weight::zero()
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            Self::update_populations(&PopulationModel::default())
+                .saturating_add(Self::sample_populations_if_due(n))
+                .saturating_add(Self::end_season_if_due(n))
+        }
+
+        fn on_idle(n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::settle_expired_auctions(n, remaining_weight)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+            Self::do_try_state()
         }
     }
 
@@ -175,113 +951,2187 @@ pub mod pallet {
     impl<T: Config> Pallet<T> {
         /// Catch a jokeymon
         ///
-        /// The jokeymon given is taken from a distribution
+        /// The jokeymon given is taken from a distribution weighted by each species' population
+        /// and how close it is to its carrying capacity, protected species are never given.
+        /// Every catch rule is checked before the fee is charged, and a failed payment is rolled
+        /// back with the rest of the call, so a failed catch leaves the region and nonces
+        /// untouched.
         #[pallet::call_index(0)]
-        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
         pub fn catch_jokeymon(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
             // get user and account data
             let who = ensure_signed(origin)?;
             let mut account_data = AccountToData::<T>::get(&who);
 
-            // get random number
-            let seed = Self::get_and_increment_random_nonce();
-            let roll = Self::get_random_number(&seed);
+            // check there is room for another jokeymon
+            ensure!(
+                account_data.jokeymon.len() < T::MaxJokeymonHoldable::get() as usize,
+                Error::<T>::TooManyJokeymon
+            );
+            let stamina = Self::catch_stamina(&who);
+            ensure!(stamina.points > 0, Error::<T>::OutOfStamina);
 
-            // check region has available jokeymon
+            // check region has a jokeymon that can be caught
             let current_region_id = account_data.current_region;
             let mut region = RegionIdToRegion::<T>::get(current_region_id);
-            if region.total_population == 0 {
-                Err(Error::<T>::NoCatchableJokeymon)?
-            }
+            let weights = Self::catch_weights(&region);
+            ensure!(
+                weights.iter().any(|(_, weight)| *weight > 0),
+                Error::<T>::NoCatchableJokeymon
+            );
+
+            // pay the catch fee and hold the deposit for the new jokeymon
+            Self::charge_fee(&who, T::CatchFee::get())?;
+            T::Currency::hold(
+                &HoldReason::JokeymonDeposit.into(),
+                &who,
+                T::JokeymonDeposit::get(),
+            )?;
 
             // decide which jokeymon species, decrement it from region
-            let caught_species_id = Self::get_jokeymon_in_region(&region, roll);
+            let seed = Self::get_and_increment_random_nonce();
+            let roll = Self::get_random_number(&seed);
+            let caught_species_id =
+                Self::pick_weighted(&weights, roll).ok_or(Error::<T>::NoCatchableJokeymon)?;
             Self::decrement_species_in_population(&mut region, caught_species_id, 1);
+            Self::refresh_protection(current_region_id, &region);
             RegionIdToRegion::<T>::set(current_region_id, region);
 
             // generate jokeymon of that species
-            let new_jokeymon_id = Self::get_and_increment_jokeymon_id_nonce();
-            // let species_data = JokeymonSpeciesIdToSpeciesData::<T>::get(caught_jokeymon_species_id);
-            // let mutated_species_data = Self::mutate_jokeymon_data(species_data);
-            let data = JokeymonData::<T> {
-                id: caught_species_id,
-                birth_date: SystemPallet::<T>::block_number(),
-            };
-
-            // add that jokeymon to the jokeymon data bank
-            JokeymonIdToData::<T>::set(new_jokeymon_id, Some(data));
-
-            // add jokeymon to a users collection
-            account_data
-                .jokeymon
-                .try_push(new_jokeymon_id)
-                .map_err(|_| Error::<T>::TooManyJokeymon)?;
+            let new_jokeymon_id = Self::mint_jokeymon(&who, &mut account_data, caught_species_id)?;
             AccountToData::<T>::set(&who, account_data);
+            // without regeneration there is no throttling, so there is nothing to track
+            if !T::CatchStaminaRegen::get().is_zero() {
+                Stamina::<T>::insert(
+                    &who,
+                    CatchStamina {
+                        points: stamina.points - 1,
+                        updated: stamina.updated,
+                    },
+                );
+            }
 
             // deposit and event
             Self::deposit_event(Event::JokeymonCaptured {
                 species_id: caught_species_id,
                 jokeymon_id: new_jokeymon_id,
-                who: who,
+                who: who.clone(),
             });
+            Self::record_caught(&who, caught_species_id);
+            Self::progress_quests(&who, caught_species_id, current_region_id);
+            if let Some(member) = GuildMembers::<T>::get(&who) {
+                Guilds::<T>::mutate(member.guild_id, |guild| {
+                    if let Some(guild) = guild {
+                        guild.catches.saturating_inc();
+                    }
+                });
+            }
 
             Ok(().into())
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        /// use and update the random nonce
-        pub(super) fn get_and_increment_random_nonce() -> Vec<u8> {
-            let val = RandomNonce::<T>::get();
-            RandomNonce::<T>::put(val.wrapping_add(1));
-            val.encode()
-        }
+        /// Release an owned jokeymon into the current region
+        ///
+        /// The deposit held for the jokeymon is returned
+        #[pallet::call_index(1)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(4))]
+        pub fn release_jokeymon(
+            origin: OriginFor<T>,
+            jokeymon_id: JokeymonId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let mut account_data = AccountToData::<T>::get(&who);
+            ensure!(
+                !LockedJokeymon::<T>::contains_key(jokeymon_id),
+                Error::<T>::JokeymonLocked
+            );
 
-        /// use and update the jokeymon unique identifier nonce
-        pub(super) fn get_and_increment_jokeymon_id_nonce() -> JokeymonId {
-            let val = JokeymonIdNonce::<T>::get();
-            JokeymonIdNonce::<T>::put(val.wrapping_add(1));
-            val
-        }
+            // remove jokeymon from the users collection
+            let index = account_data
+                .jokeymon
+                .iter()
+                .position(|id| *id == jokeymon_id)
+                .ok_or(Error::<T>::NotJokeymonOwner)?;
+            account_data.jokeymon.remove(index);
+            account_data.party.retain(|id| *id != jokeymon_id);
 
-        /// get a random number given the nonce
-        pub(super) fn get_random_number(seed: &Vec<u8>) -> Permill {
-            let (random, _) = T::RandomSource::random(seed);
-            let as_bytes = random.encode();
-            let part = u32::from_le_bytes([as_bytes[0], as_bytes[1], as_bytes[2], as_bytes[3]]);
-            Permill::from_rational(part, u32::MAX)
-        }
+            // put it back into the region population
+            let data =
+                JokeymonIdToData::<T>::take(jokeymon_id).ok_or(Error::<T>::NotJokeymonOwner)?;
+            JokeymonIdToOwner::<T>::remove(jokeymon_id);
+            Experience::<T>::remove(jokeymon_id);
+            let current_region_id = account_data.current_region;
+            let mut region = RegionIdToRegion::<T>::get(current_region_id);
+            Self::increment_species_in_population(&mut region, data.id, 1)?;
+            Self::refresh_protection(current_region_id, &region);
+            RegionIdToRegion::<T>::set(current_region_id, region);
+            AccountToData::<T>::set(&who, account_data);
 
-        /// get a jokeymon in a region, given a random number
-        pub(super) fn get_jokeymon_in_region(
-            region: &Region<T>,
-            mut catch_roll: Permill,
-        ) -> JokeymonSpeciesId {
-            let eps = Permill::from_parts(1u32);
-            let mut eps_added = false;
+            // return the deposit
+            T::Currency::release(
+                &HoldReason::JokeymonDeposit.into(),
+                &who,
+                T::JokeymonDeposit::get(),
+                Precision::BestEffort,
+            )?;
 
-            // generate chances based on population size
-            let total = region.total_population;
-            let sub_totals = &region.population_demographics;
-            let mut jokeymon_chances = Vec::new();
-            for (id, size) in sub_totals.iter() {
-                let mut p = Permill::from_rational((*size).into(), total);
-                if !eps_added {
-                    // Just once to offset rounding issue
-                    p = p.saturating_add(eps);
-                    eps_added = true;
-                }
-                jokeymon_chances.push((*id, p));
-            }
+            Self::deposit_event(Event::JokeymonReleased {
+                species_id: data.id,
+                jokeymon_id,
+                region_id: current_region_id,
+                who,
+            });
 
-            // pick species based on chances
-            for (id, rate) in jokeymon_chances.iter() {
-                catch_roll = catch_roll.saturating_sub(*rate);
-                if catch_roll == Permill::zero() {
-                    return *id;
-                }
+            Ok(().into())
+        }
+
+        /// Travel to another region
+        #[pallet::call_index(2)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn travel(origin: OriginFor<T>, region_id: RegionId) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let mut account_data = AccountToData::<T>::get(&who);
+
+            let from = account_data.current_region;
+            ensure!(from != region_id, Error::<T>::AlreadyInRegion);
+            ensure!(
+                RegionIdToRegion::<T>::contains_key(region_id),
+                Error::<T>::UnknownRegion
+            );
+
+            Self::charge_fee(&who, T::TravelFee::get())?;
+            account_data.current_region = region_id;
+            AccountToData::<T>::set(&who, account_data);
+
+            Self::deposit_event(Event::Travelled {
+                who,
+                from,
+                to: region_id,
+            });
+
+            Ok(().into())
+        }
+
+        /// Give an owned jokeymon to another account
+        ///
+        /// The receiver takes over the jokeymon's deposit
+        #[pallet::call_index(3)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(3))]
+        pub fn transfer_jokeymon(
+            origin: OriginFor<T>,
+            dest: T::AccountId,
+            jokeymon_id: JokeymonId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                JokeymonIdToOwner::<T>::get(jokeymon_id).as_ref() == Some(&who),
+                Error::<T>::NotJokeymonOwner
+            );
+            Self::do_transfer(jokeymon_id, &dest)?;
+            Ok(().into())
+        }
+
+        /// List an owned jokeymon for sale
+        ///
+        /// The jokeymon is locked until it is sold or the listing is cancelled
+        #[pallet::call_index(4)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn list_for_sale(
+            origin: OriginFor<T>,
+            jokeymon_id: JokeymonId,
+            price: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let seller = ensure_signed(origin)?;
+            ensure!(
+                JokeymonIdToOwner::<T>::get(jokeymon_id).as_ref() == Some(&seller),
+                Error::<T>::NotJokeymonOwner
+            );
+            ensure!(
+                !Listings::<T>::contains_key(jokeymon_id),
+                Error::<T>::AlreadyListed
+            );
+            ensure!(
+                !LockedJokeymon::<T>::contains_key(jokeymon_id),
+                Error::<T>::JokeymonLocked
+            );
+
+            Self::lock_jokeymon(jokeymon_id, LockReason::Listing);
+            Listings::<T>::insert(
+                jokeymon_id,
+                Listing {
+                    seller: seller.clone(),
+                    price,
+                },
+            );
+
+            Self::deposit_event(Event::JokeymonListed {
+                jokeymon_id,
+                seller,
+                price,
+            });
+            Ok(().into())
+        }
+
+        /// Cancel a listing, unlocking the jokeymon
+        #[pallet::call_index(5)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn cancel_listing(
+            origin: OriginFor<T>,
+            jokeymon_id: JokeymonId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let listing = Listings::<T>::get(jokeymon_id).ok_or(Error::<T>::NotListed)?;
+            ensure!(listing.seller == who, Error::<T>::NotJokeymonOwner);
+
+            Listings::<T>::remove(jokeymon_id);
+            Self::unlock_jokeymon(jokeymon_id, LockReason::Listing);
+
+            Self::deposit_event(Event::ListingCancelled {
+                jokeymon_id,
+                seller: who,
+            });
+            Ok(().into())
+        }
+
+        /// Buy a listed jokeymon at its asking price
+        ///
+        /// The marketplace fee goes to the treasury, the rest to the seller
+        #[pallet::call_index(6)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(5))]
+        pub fn buy(origin: OriginFor<T>, jokeymon_id: JokeymonId) -> DispatchResultWithPostInfo {
+            let buyer = ensure_signed(origin)?;
+            let Listing { seller, price } =
+                Listings::<T>::take(jokeymon_id).ok_or(Error::<T>::NotListed)?;
+            ensure!(seller != buyer, Error::<T>::CannotBuyOwnJokeymon);
+            Self::ensure_still_owned(jokeymon_id, &seller)?;
+
+            // pay the treasury and the seller
+            let fee = Self::pay_for_sale(&buyer, &seller, price)?;
+
+            // hand over the jokeymon
+            Self::unlock_jokeymon(jokeymon_id, LockReason::Listing);
+            Self::do_transfer(jokeymon_id, &buyer)?;
+
+            Self::deposit_event(Event::JokeymonSold {
+                jokeymon_id,
+                seller,
+                buyer,
+                price,
+                fee,
+            });
+            Ok(().into())
+        }
+
+        /// Put an owned jokeymon up for auction for `duration` blocks
+        ///
+        /// The jokeymon is locked until the auction ends
+        #[pallet::call_index(7)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(3))]
+        pub fn create_auction(
+            origin: OriginFor<T>,
+            jokeymon_id: JokeymonId,
+            kind: AuctionKind<BalanceOf<T>>,
+            duration: BlockNumberFor<T>,
+        ) -> DispatchResultWithPostInfo {
+            let seller = ensure_signed(origin)?;
+            ensure!(
+                JokeymonIdToOwner::<T>::get(jokeymon_id).as_ref() == Some(&seller),
+                Error::<T>::NotJokeymonOwner
+            );
+            ensure!(
+                !LockedJokeymon::<T>::contains_key(jokeymon_id),
+                Error::<T>::JokeymonLocked
+            );
+            ensure!(
+                !duration.is_zero() && duration <= T::MaxAuctionDuration::get(),
+                Error::<T>::InvalidAuction
+            );
+            if let AuctionKind::Dutch {
+                start_price,
+                floor_price,
+            } = &kind
+            {
+                ensure!(start_price >= floor_price, Error::<T>::InvalidAuction);
+            }
+
+            // queue the auction by its end block
+            let start = SystemPallet::<T>::block_number();
+            let end = start.saturating_add(duration);
+            let mut queue = AuctionQueue::<T>::get();
+            let index = queue.partition_point(|(queued_end, _)| *queued_end <= end);
+            queue
+                .try_insert(index, (end, jokeymon_id))
+                .map_err(|_| Error::<T>::TooManyAuctions)?;
+            AuctionQueue::<T>::put(queue);
+
+            Self::lock_jokeymon(jokeymon_id, LockReason::Auction);
+            Auctions::<T>::insert(
+                jokeymon_id,
+                Auction {
+                    seller: seller.clone(),
+                    kind: kind.clone(),
+                    start,
+                    end,
+                    highest_bid: None,
+                },
+            );
+
+            Self::deposit_event(Event::AuctionCreated {
+                jokeymon_id,
+                seller,
+                kind,
+                end,
+            });
+            Ok(().into())
+        }
+
+        /// Bid on an auction
+        ///
+        /// English auction bids are held until outbid or the auction ends, a dutch
+        /// auction is won straight away by the first bid at or above the current price
+        #[pallet::call_index(8)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(6))]
+        pub fn bid(
+            origin: OriginFor<T>,
+            jokeymon_id: JokeymonId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let bidder = ensure_signed(origin)?;
+            let mut auction = Auctions::<T>::get(jokeymon_id).ok_or(Error::<T>::AuctionNotFound)?;
+            let now = SystemPallet::<T>::block_number();
+            ensure!(now < auction.end, Error::<T>::AuctionEnded);
+            ensure!(auction.seller != bidder, Error::<T>::CannotBuyOwnJokeymon);
+
+            match auction.kind {
+                AuctionKind::English { min_bid } => {
+                    let beats_highest = auction
+                        .highest_bid
+                        .as_ref()
+                        .is_none_or(|(_, highest)| amount > *highest);
+                    ensure!(amount >= min_bid && beats_highest, Error::<T>::BidTooLow);
+
+                    // hold the new bid, refund the previous one
+                    let reason = HoldReason::AuctionBid.into();
+                    T::Currency::hold(&reason, &bidder, amount)?;
+                    if let Some((previous, previous_amount)) = auction.highest_bid.take() {
+                        T::Currency::release(
+                            &reason,
+                            &previous,
+                            previous_amount,
+                            Precision::BestEffort,
+                        )?;
+                        Self::deposit_event(Event::BidRefunded {
+                            jokeymon_id,
+                            bidder: previous,
+                            amount: previous_amount,
+                        });
+                    }
+                    auction.highest_bid = Some((bidder.clone(), amount));
+                    Auctions::<T>::insert(jokeymon_id, auction);
+
+                    Self::deposit_event(Event::BidPlaced {
+                        jokeymon_id,
+                        bidder,
+                        amount,
+                    });
+                }
+                AuctionKind::Dutch { .. } => {
+                    let price = Self::dutch_auction_price(&auction, now);
+                    ensure!(amount >= price, Error::<T>::BidTooLow);
+                    Self::ensure_still_owned(jokeymon_id, &auction.seller)?;
+
+                    Auctions::<T>::remove(jokeymon_id);
+                    AuctionQueue::<T>::mutate(|queue| queue.retain(|(_, id)| *id != jokeymon_id));
+                    Self::unlock_jokeymon(jokeymon_id, LockReason::Auction);
+
+                    let fee = Self::pay_for_sale(&bidder, &auction.seller, price)?;
+                    Self::do_transfer(jokeymon_id, &bidder)?;
+
+                    Self::deposit_event(Event::AuctionSettled {
+                        jokeymon_id,
+                        seller: auction.seller,
+                        buyer: bidder,
+                        price,
+                        fee,
+                    });
+                }
+            }
+            Ok(().into())
+        }
+
+        /// Challenge another account to a battle, optionally staking a wager
+        ///
+        /// The team is picked from the caller's party and borrowed jokeymon. It is locked and
+        /// the wager held until the challenge is answered.
+        #[pallet::call_index(9)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(3))]
+        pub fn challenge(
+            origin: OriginFor<T>,
+            opponent: T::AccountId,
+            my_team: Team<T>,
+            wager: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let challenger = ensure_signed(origin)?;
+            ensure!(challenger != opponent, Error::<T>::CannotChallengeSelf);
+            ensure!(
+                !Challenges::<T>::contains_key(&opponent),
+                Error::<T>::AlreadyChallenged
+            );
+            Self::ensure_valid_team(&challenger, &my_team)?;
+            for jokeymon_id in my_team.iter() {
+                ensure!(
+                    !LockedJokeymon::<T>::contains_key(jokeymon_id),
+                    Error::<T>::JokeymonLocked
+                );
+            }
+
+            if !wager.is_zero() {
+                T::Currency::hold(&HoldReason::BattleWager.into(), &challenger, wager)?;
+            }
+            for jokeymon_id in my_team.iter() {
+                Self::lock_jokeymon(*jokeymon_id, LockReason::Challenge);
+            }
+            Challenges::<T>::insert(
+                &opponent,
+                Challenge {
+                    challenger: challenger.clone(),
+                    team: my_team,
+                    wager,
+                },
+            );
+
+            Self::deposit_event(Event::ChallengeIssued {
+                challenger,
+                opponent,
+                wager,
+            });
+            Ok(().into())
+        }
+
+        /// Accept the waiting challenge and fight it out
+        ///
+        /// The opponent matches the wager, the winner takes both stakes and its party shares
+        /// the battle experience
+        #[pallet::call_index(10)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(17, 15))]
+        pub fn accept_challenge(
+            origin: OriginFor<T>,
+            my_team: Team<T>,
+        ) -> DispatchResultWithPostInfo {
+            let opponent = ensure_signed(origin)?;
+            let challenge =
+                Challenges::<T>::take(&opponent).ok_or(Error::<T>::ChallengeNotFound)?;
+            Self::ensure_valid_team(&opponent, &my_team)?;
+            for jokeymon_id in challenge.team.iter() {
+                Self::unlock_jokeymon(*jokeymon_id, LockReason::Challenge);
+            }
+
+            let reason = HoldReason::BattleWager.into();
+            if !challenge.wager.is_zero() {
+                T::Currency::hold(&reason, &opponent, challenge.wager)?;
+            }
+
+            // both sides get a look at the other team
+            Self::record_seen(&challenge.challenger, &my_team);
+            Self::record_seen(&opponent, &challenge.team);
+
+            // fight
+            let result = battle::resolve(
+                Self::team_fighters(&challenge.team),
+                Self::team_fighters(&my_team),
+                T::MaxBattleRounds::get(),
+                Self::battle_rolls(),
+            );
+            let (winner, loser) = match result.outcome {
+                BattleOutcome::FirstWins => (Some(&challenge.challenger), Some(&opponent)),
+                BattleOutcome::SecondWins => (Some(&opponent), Some(&challenge.challenger)),
+                BattleOutcome::Draw => (None, None),
+            };
+
+            // pay out the wager
+            if !challenge.wager.is_zero() {
+                match (winner, loser) {
+                    (Some(winner), Some(loser)) => {
+                        T::Currency::transfer_on_hold(
+                            &reason,
+                            loser,
+                            winner,
+                            challenge.wager,
+                            Precision::BestEffort,
+                            Restriction::Free,
+                            Fortitude::Polite,
+                        )?;
+                        T::Currency::release(
+                            &reason,
+                            winner,
+                            challenge.wager,
+                            Precision::BestEffort,
+                        )?;
+                    }
+                    _ => {
+                        for who in [&challenge.challenger, &opponent] {
+                            T::Currency::release(
+                                &reason,
+                                who,
+                                challenge.wager,
+                                Precision::BestEffort,
+                            )?;
+                        }
+                    }
+                }
+            }
+
+            // record results
+            match (winner, loser) {
+                (Some(winner), Some(loser)) => {
+                    BattleRecords::<T>::mutate(winner, |r| r.wins.saturating_inc());
+                    BattleRecords::<T>::mutate(loser, |r| r.losses.saturating_inc());
+                }
+                _ => {
+                    BattleRecords::<T>::mutate(&challenge.challenger, |r| r.draws.saturating_inc());
+                    BattleRecords::<T>::mutate(&opponent, |r| r.draws.saturating_inc());
+                }
+            }
+            if let Some(winner) = winner {
+                Self::share_experience(winner);
+            }
+
+            Self::deposit_event(Event::BattleResolved {
+                winner: winner.cloned(),
+                challenger: challenge.challenger,
+                opponent,
+                rounds: result.rounds,
+                wager: challenge.wager,
+            });
+            Ok(().into())
+        }
+
+        /// Withdraw a challenge as the challenger, or decline it as the opponent
+        #[pallet::call_index(11)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(8))]
+        pub fn cancel_challenge(
+            origin: OriginFor<T>,
+            opponent: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let challenge = Challenges::<T>::get(&opponent).ok_or(Error::<T>::ChallengeNotFound)?;
+            ensure!(
+                who == challenge.challenger || who == opponent,
+                Error::<T>::ChallengeNotFound
+            );
+
+            Challenges::<T>::remove(&opponent);
+            for jokeymon_id in challenge.team.iter() {
+                Self::unlock_jokeymon(*jokeymon_id, LockReason::Challenge);
+            }
+            if !challenge.wager.is_zero() {
+                T::Currency::release(
+                    &HoldReason::BattleWager.into(),
+                    &challenge.challenger,
+                    challenge.wager,
+                    Precision::BestEffort,
+                )?;
+            }
+
+            Self::deposit_event(Event::ChallengeCancelled {
+                challenger: challenge.challenger,
+                opponent,
+            });
+            Ok(().into())
+        }
+
+        /// Play a rated match against the party of another account on the ladder
+        ///
+        /// Both accounts have to be on the ladder, and can only play each other once every
+        /// `RatedMatchCooldown` blocks. Both ratings for the current season are updated with
+        /// the result, and the winner's party shares the battle experience.
+        #[pallet::call_index(12)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(23, 12))]
+        pub fn rated_match(
+            origin: OriginFor<T>,
+            opponent: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            let player = ensure_signed(origin)?;
+            ensure!(player != opponent, Error::<T>::CannotChallengeSelf);
+            ensure!(
+                LadderPlayers::<T>::contains_key(&player)
+                    && LadderPlayers::<T>::contains_key(&opponent),
+                Error::<T>::NotOnLadder
+            );
+            let now = SystemPallet::<T>::block_number();
+            let (first, second) = if player < opponent {
+                (&player, &opponent)
+            } else {
+                (&opponent, &player)
+            };
+            if let Some(last) = LastRatedMatch::<T>::get(first, second) {
+                ensure!(
+                    now >= last.saturating_add(T::RatedMatchCooldown::get()),
+                    Error::<T>::RatedMatchTooSoon
+                );
+            }
+            LastRatedMatch::<T>::insert(first, second, now);
+
+            let player_team = &AccountToData::<T>::get(&player).party[..];
+            let opponent_team = &AccountToData::<T>::get(&opponent).party[..];
+            let player_fighters = Self::team_fighters(player_team);
+            let opponent_fighters = Self::team_fighters(opponent_team);
+            ensure!(
+                !player_fighters.is_empty() && !opponent_fighters.is_empty(),
+                Error::<T>::NoJokeymonToBattle
+            );
+            Self::record_seen(&player, opponent_team);
+            Self::record_seen(&opponent, player_team);
+
+            let result = battle::resolve(
+                player_fighters,
+                opponent_fighters,
+                T::MaxBattleRounds::get(),
+                Self::battle_rolls(),
+            );
+            let (player_score, winner) = match result.outcome {
+                BattleOutcome::FirstWins => (Score::Win, Some(player.clone())),
+                BattleOutcome::SecondWins => (Score::Loss, Some(opponent.clone())),
+                BattleOutcome::Draw => (Score::Draw, None),
+            };
+            let opponent_score = match player_score {
+                Score::Win => Score::Loss,
+                Score::Draw => Score::Draw,
+                Score::Loss => Score::Win,
+            };
+
+            // rate both accounts against their ratings before the match
+            let season = CurrentSeason::<T>::get();
+            let player_before = Ratings::<T>::get(season, &player);
+            let opponent_before = Ratings::<T>::get(season, &opponent);
+            let player_rating =
+                rating::updated_rating(player_before, opponent_before, player_score);
+            let opponent_rating =
+                rating::updated_rating(opponent_before, player_before, opponent_score);
+            Ratings::<T>::insert(season, &player, player_rating);
+            Ratings::<T>::insert(season, &opponent, opponent_rating);
+            Self::update_leaderboard(&player, player_rating);
+            Self::update_leaderboard(&opponent, opponent_rating);
+            if let Some(winner) = &winner {
+                Self::share_experience(winner);
+            }
+
+            Self::deposit_event(Event::RatedMatchPlayed {
+                player,
+                opponent,
+                winner,
+                player_rating,
+                opponent_rating,
+            });
+            Ok(().into())
+        }
+
+        /// Open a new quest
+        #[pallet::call_index(13)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(3))]
+        pub fn create_quest(
+            origin: OriginFor<T>,
+            objective: QuestObjective,
+            reward: QuestReward<BalanceOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            T::AdminOrigin::ensure_origin(origin)?;
+            let QuestObjective::Catch { count, .. } = objective;
+            ensure!(count > 0, Error::<T>::InvalidQuest);
+
+            let quest_id = QuestNonce::<T>::get();
+            OpenQuests::<T>::try_append(quest_id).map_err(|_| Error::<T>::TooManyQuests)?;
+            QuestNonce::<T>::put(quest_id.saturating_add(1));
+            Quests::<T>::insert(quest_id, Quest { objective, reward });
+
+            Self::deposit_event(Event::QuestCreated { quest_id });
+            Ok(().into())
+        }
+
+        /// Close a quest, unclaimed rewards are forfeit
+        #[pallet::call_index(14)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn remove_quest(origin: OriginFor<T>, quest_id: QuestId) -> DispatchResultWithPostInfo {
+            T::AdminOrigin::ensure_origin(origin)?;
+            Quests::<T>::take(quest_id).ok_or(Error::<T>::UnknownQuest)?;
+            OpenQuests::<T>::mutate(|quests| quests.retain(|id| *id != quest_id));
+
+            Self::deposit_event(Event::QuestRemoved { quest_id });
+            Ok(().into())
+        }
+
+        /// Claim the reward of a completed quest
+        #[pallet::call_index(15)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(6))]
+        pub fn claim_quest_reward(
+            origin: OriginFor<T>,
+            quest_id: QuestId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let quest = Quests::<T>::get(quest_id).ok_or(Error::<T>::UnknownQuest)?;
+            let mut progress = QuestProgresses::<T>::get(&who, quest_id);
+            ensure!(!progress.claimed, Error::<T>::QuestAlreadyClaimed);
+            let QuestObjective::Catch { count, .. } = quest.objective;
+            ensure!(progress.count >= count, Error::<T>::QuestNotComplete);
+
+            match quest.reward {
+                QuestReward::Tokens(amount) => {
+                    T::Currency::transfer(
+                        &Self::account_id(),
+                        &who,
+                        amount,
+                        Preservation::Preserve,
+                    )?;
+                }
+                QuestReward::Jokeymon(species_id) => {
+                    T::Currency::hold(
+                        &HoldReason::JokeymonDeposit.into(),
+                        &who,
+                        T::JokeymonDeposit::get(),
+                    )?;
+                    let mut account_data = AccountToData::<T>::get(&who);
+                    let jokeymon_id = Self::mint_jokeymon(&who, &mut account_data, species_id)?;
+                    AccountToData::<T>::set(&who, account_data);
+                    Self::deposit_event(Event::JokeymonRewarded {
+                        species_id,
+                        jokeymon_id,
+                        who: who.clone(),
+                    });
+                }
+            }
+            progress.claimed = true;
+            QuestProgresses::<T>::insert(&who, quest_id, progress);
+
+            Self::deposit_event(Event::QuestRewardClaimed {
+                who,
+                quest_id,
+                reward: quest.reward,
+            });
+            Ok(().into())
+        }
+
+        /// Make a region host a gym
+        #[pallet::call_index(16)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn create_gym(origin: OriginFor<T>, region_id: RegionId) -> DispatchResultWithPostInfo {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(
+                RegionIdToRegion::<T>::contains_key(region_id),
+                Error::<T>::UnknownRegion
+            );
+            ensure!(
+                !Gyms::<T>::contains_key(region_id),
+                Error::<T>::GymAlreadyExists
+            );
+            Gyms::<T>::insert(region_id, Gym::<T>::default());
+
+            Self::deposit_event(Event::GymCreated { region_id });
+            Ok(().into())
+        }
+
+        /// Close a region's gym, paying out and freeing the defending team
+        #[pallet::call_index(17)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(9))]
+        pub fn remove_gym(origin: OriginFor<T>, region_id: RegionId) -> DispatchResultWithPostInfo {
+            T::AdminOrigin::ensure_origin(origin)?;
+            let mut gym = Gyms::<T>::take(region_id).ok_or(Error::<T>::GymNotFound)?;
+            Self::pay_gym_reward(region_id, &mut gym);
+            for jokeymon_id in gym.team.iter() {
+                Self::unlock_jokeymon(*jokeymon_id, LockReason::Gym);
+            }
+
+            Self::deposit_event(Event::GymRemoved { region_id });
+            Ok(().into())
+        }
+
+        /// Challenge the gym in the account's current region
+        ///
+        /// An empty gym is taken without a fight, otherwise the challenger has to beat the
+        /// defending team outright and the winner's party shares the battle experience. The
+        /// winning team stays locked in the gym to defend it.
+        #[pallet::call_index(18)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(21, 23))]
+        pub fn challenge_gym(origin: OriginFor<T>, team: Team<T>) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let region_id = AccountToData::<T>::get(&who).current_region;
+            let mut gym = Gyms::<T>::get(region_id).ok_or(Error::<T>::GymNotFound)?;
+            ensure!(
+                gym.controller.as_ref() != Some(&who),
+                Error::<T>::AlreadyGymController
+            );
+            Self::ensure_valid_team(&who, &team)?;
+            for jokeymon_id in team.iter() {
+                ensure!(
+                    !LockedJokeymon::<T>::contains_key(jokeymon_id),
+                    Error::<T>::JokeymonLocked
+                );
+            }
+
+            if let Some(controller) = gym.controller.clone() {
+                Self::record_seen(&who, &gym.team);
+                Self::record_seen(&controller, &team);
+                let result = battle::resolve(
+                    Self::team_fighters(&team),
+                    Self::team_fighters(&gym.team),
+                    T::MaxBattleRounds::get(),
+                    Self::battle_rolls(),
+                );
+                if result.outcome != BattleOutcome::FirstWins {
+                    if result.outcome == BattleOutcome::SecondWins {
+                        Self::share_experience(&controller);
+                    }
+                    Self::deposit_event(Event::GymDefended {
+                        region_id,
+                        controller,
+                        challenger: who,
+                    });
+                    return Ok(().into());
+                }
+                Self::share_experience(&who);
+                Self::pay_gym_reward(region_id, &mut gym);
+            }
+
+            for jokeymon_id in gym.team.iter() {
+                Self::unlock_jokeymon(*jokeymon_id, LockReason::Gym);
+            }
+            for jokeymon_id in team.iter() {
+                Self::lock_jokeymon(*jokeymon_id, LockReason::Gym);
+            }
+            let previous = gym.controller.replace(who.clone());
+            gym.team = team;
+            gym.paid_until = SystemPallet::<T>::block_number();
+            Gyms::<T>::insert(region_id, gym);
+
+            Self::deposit_event(Event::GymCaptured {
+                region_id,
+                who,
+                previous,
+            });
+            Ok(().into())
+        }
+
+        /// Give up a controlled gym, collecting its reward and freeing the defending team
+        #[pallet::call_index(19)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(9))]
+        pub fn abandon_gym(
+            origin: OriginFor<T>,
+            region_id: RegionId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let mut gym = Gyms::<T>::get(region_id).ok_or(Error::<T>::GymNotFound)?;
+            ensure!(
+                gym.controller.as_ref() == Some(&who),
+                Error::<T>::NotGymController
+            );
+            Self::pay_gym_reward(region_id, &mut gym);
+            for jokeymon_id in gym.team.iter() {
+                Self::unlock_jokeymon(*jokeymon_id, LockReason::Gym);
+            }
+            Gyms::<T>::insert(region_id, Gym::<T>::default());
+
+            Self::deposit_event(Event::GymAbandoned { region_id, who });
+            Ok(().into())
+        }
+
+        /// Collect the reward a controlled gym has earned so far
+        #[pallet::call_index(20)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(3))]
+        pub fn claim_gym_reward(
+            origin: OriginFor<T>,
+            region_id: RegionId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let mut gym = Gyms::<T>::get(region_id).ok_or(Error::<T>::GymNotFound)?;
+            ensure!(
+                gym.controller.as_ref() == Some(&who),
+                Error::<T>::NotGymController
+            );
+            Self::pay_gym_reward(region_id, &mut gym);
+            Gyms::<T>::insert(region_id, gym);
+            Ok(().into())
+        }
+
+        /// Found a new guild, led by the caller
+        #[pallet::call_index(21)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(3))]
+        pub fn create_guild(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                !GuildMembers::<T>::contains_key(&who),
+                Error::<T>::AlreadyInGuild
+            );
+
+            let guild_id = GuildNonce::<T>::get();
+            GuildNonce::<T>::put(guild_id.saturating_add(1));
+            Guilds::<T>::insert(
+                guild_id,
+                Guild {
+                    members: 1,
+                    catches: 0,
+                },
+            );
+            GuildMembers::<T>::insert(
+                &who,
+                GuildMember {
+                    guild_id,
+                    role: GuildRole::Leader,
+                    borrowed: 0,
+                },
+            );
+
+            Self::deposit_event(Event::GuildCreated {
+                guild_id,
+                founder: who,
+            });
+            Ok(().into())
+        }
+
+        /// Join a guild as a member
+        #[pallet::call_index(22)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn join_guild(origin: OriginFor<T>, guild_id: GuildId) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                !GuildMembers::<T>::contains_key(&who),
+                Error::<T>::AlreadyInGuild
+            );
+            Guilds::<T>::try_mutate(guild_id, |guild| -> DispatchResult {
+                let guild = guild.as_mut().ok_or(Error::<T>::UnknownGuild)?;
+                ensure!(
+                    guild.members < T::MaxGuildMembers::get(),
+                    Error::<T>::GuildFull
+                );
+                guild.members += 1;
+                Ok(())
+            })?;
+            GuildMembers::<T>::insert(
+                &who,
+                GuildMember {
+                    guild_id,
+                    role: GuildRole::Member,
+                    borrowed: 0,
+                },
+            );
+
+            Self::deposit_event(Event::GuildJoined { guild_id, who });
+            Ok(().into())
+        }
+
+        /// Leave the caller's guild
+        ///
+        /// The leader can only leave as the last member, which dissolves the guild and pays
+        /// out what is left in its pot
+        #[pallet::call_index(23)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(4))]
+        pub fn leave_guild(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let member = GuildMembers::<T>::get(&who).ok_or(Error::<T>::NotInGuild)?;
+            ensure!(member.borrowed == 0, Error::<T>::HasBorrowedJokeymon);
+            let guild_id = member.guild_id;
+            let mut guild = Guilds::<T>::get(guild_id).ok_or(Error::<T>::UnknownGuild)?;
+
+            if guild.members > 1 {
+                ensure!(
+                    member.role != GuildRole::Leader,
+                    Error::<T>::LeaderCannotLeave
+                );
+                guild.members -= 1;
+                Guilds::<T>::insert(guild_id, guild);
+                GuildMembers::<T>::remove(&who);
+                Self::deposit_event(Event::GuildLeft { guild_id, who });
+                return Ok(().into());
+            }
+
+            // last one out closes the guild
+            let pot = Self::guild_account_id(guild_id);
+            ensure!(
+                AccountToData::<T>::get(&pot).jokeymon.is_empty(),
+                Error::<T>::GuildRosterNotEmpty
+            );
+            let funds = <T::Currency as fungible::Inspect<_>>::reducible_balance(
+                &pot,
+                Preservation::Expendable,
+                Fortitude::Polite,
+            );
+            if !funds.is_zero() {
+                T::Currency::transfer(&pot, &who, funds, Preservation::Expendable)?;
+            }
+            AccountToData::<T>::remove(&pot);
+            Guilds::<T>::remove(guild_id);
+            GuildMembers::<T>::remove(&who);
+
+            Self::deposit_event(Event::GuildLeft { guild_id, who });
+            Self::deposit_event(Event::GuildDissolved { guild_id });
+            Ok(().into())
+        }
+
+        /// Change the role of a member of the caller's guild
+        ///
+        /// Only the leader sets roles, making someone else leader demotes the caller to officer
+        #[pallet::call_index(24)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn set_guild_role(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+            role: GuildRole,
+        ) -> DispatchResultWithPostInfo {
+            let leader = ensure_signed(origin)?;
+            let guild_id = Self::ensure_guild_role(&leader, GuildRole::Leader)?;
+            ensure!(leader != who, Error::<T>::InsufficientGuildRole);
+            GuildMembers::<T>::try_mutate(&who, |member| -> DispatchResult {
+                let member = member
+                    .as_mut()
+                    .filter(|m| m.guild_id == guild_id)
+                    .ok_or(Error::<T>::NotInGuild)?;
+                member.role = role;
+                Ok(())
+            })?;
+            if role == GuildRole::Leader {
+                GuildMembers::<T>::mutate(&leader, |member| {
+                    if let Some(member) = member {
+                        member.role = GuildRole::Officer;
+                    }
+                });
+                Self::deposit_event(Event::GuildRoleSet {
+                    guild_id,
+                    who: leader,
+                    role: GuildRole::Officer,
+                });
+            }
+
+            Self::deposit_event(Event::GuildRoleSet {
+                guild_id,
+                who,
+                role,
+            });
+            Ok(().into())
+        }
+
+        /// Remove a member with a lower role from the caller's guild
+        #[pallet::call_index(25)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn kick_from_guild(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            let officer = ensure_signed(origin)?;
+            let guild_id = Self::ensure_guild_role(&officer, GuildRole::Officer)?;
+            let officer_role = GuildMembers::<T>::get(&officer).map(|m| m.role);
+            let member = GuildMembers::<T>::get(&who)
+                .filter(|m| m.guild_id == guild_id)
+                .ok_or(Error::<T>::NotInGuild)?;
+            ensure!(
+                Some(member.role) < officer_role,
+                Error::<T>::InsufficientGuildRole
+            );
+            ensure!(member.borrowed == 0, Error::<T>::HasBorrowedJokeymon);
+
+            Guilds::<T>::mutate(guild_id, |guild| {
+                if let Some(guild) = guild {
+                    guild.members.saturating_dec();
+                }
+            });
+            GuildMembers::<T>::remove(&who);
+
+            Self::deposit_event(Event::GuildLeft { guild_id, who });
+            Ok(().into())
+        }
+
+        /// Pay into the caller's guild pot
+        #[pallet::call_index(26)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn fund_guild(
+            origin: OriginFor<T>,
+            amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let guild_id = Self::ensure_guild_role(&who, GuildRole::Member)?;
+            T::Currency::transfer(
+                &who,
+                &Self::guild_account_id(guild_id),
+                amount,
+                Preservation::Preserve,
+            )?;
+
+            Self::deposit_event(Event::GuildFunded {
+                guild_id,
+                who,
+                amount,
+            });
+            Ok(().into())
+        }
+
+        /// Spend from the caller's guild pot, leader only
+        #[pallet::call_index(27)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn withdraw_guild_funds(
+            origin: OriginFor<T>,
+            dest: T::AccountId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let guild_id = Self::ensure_guild_role(&who, GuildRole::Leader)?;
+            T::Currency::transfer(
+                &Self::guild_account_id(guild_id),
+                &dest,
+                amount,
+                Preservation::Preserve,
+            )?;
+
+            Self::deposit_event(Event::GuildFundsWithdrawn {
+                guild_id,
+                dest,
+                amount,
+            });
+            Ok(().into())
+        }
+
+        /// Give an owned jokeymon to the caller's guild roster
+        ///
+        /// The guild pot holds the jokeymon's deposit from now on
+        #[pallet::call_index(28)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(5))]
+        pub fn deposit_to_roster(
+            origin: OriginFor<T>,
+            jokeymon_id: JokeymonId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let guild_id = Self::ensure_guild_role(&who, GuildRole::Member)?;
+            ensure!(
+                JokeymonIdToOwner::<T>::get(jokeymon_id).as_ref() == Some(&who),
+                Error::<T>::NotJokeymonOwner
+            );
+            Self::do_transfer(jokeymon_id, &Self::guild_account_id(guild_id))?;
+            Ok(().into())
+        }
+
+        /// Take a jokeymon out of the caller's guild roster, officers only
+        #[pallet::call_index(29)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(5))]
+        pub fn withdraw_from_roster(
+            origin: OriginFor<T>,
+            jokeymon_id: JokeymonId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let guild_id = Self::ensure_guild_role(&who, GuildRole::Officer)?;
+            Self::ensure_in_roster(guild_id, jokeymon_id)?;
+            ensure!(
+                !BorrowedJokeymon::<T>::contains_key(jokeymon_id),
+                Error::<T>::JokeymonBorrowed
+            );
+            Self::do_transfer(jokeymon_id, &who)?;
+            Ok(().into())
+        }
+
+        /// Borrow a jokeymon from the caller's guild roster to battle with
+        #[pallet::call_index(30)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn borrow_from_roster(
+            origin: OriginFor<T>,
+            jokeymon_id: JokeymonId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let guild_id = Self::ensure_guild_role(&who, GuildRole::Member)?;
+            Self::ensure_in_roster(guild_id, jokeymon_id)?;
+            ensure!(
+                !BorrowedJokeymon::<T>::contains_key(jokeymon_id),
+                Error::<T>::JokeymonBorrowed
+            );
+
+            BorrowedJokeymon::<T>::insert(jokeymon_id, &who);
+            GuildMembers::<T>::mutate(&who, |member| {
+                if let Some(member) = member {
+                    member.borrowed.saturating_inc();
+                }
+            });
+
+            Self::deposit_event(Event::JokeymonBorrowed {
+                guild_id,
+                jokeymon_id,
+                who,
+            });
+            Ok(().into())
+        }
+
+        /// Give a borrowed jokeymon back, as the borrower or an officer recalling it
+        #[pallet::call_index(31)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn return_to_roster(
+            origin: OriginFor<T>,
+            jokeymon_id: JokeymonId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let borrower =
+                BorrowedJokeymon::<T>::get(jokeymon_id).ok_or(Error::<T>::NotBorrowed)?;
+            let guild_id = GuildMembers::<T>::get(&borrower)
+                .ok_or(Error::<T>::NotInGuild)?
+                .guild_id;
+            if who != borrower {
+                ensure!(
+                    Self::ensure_guild_role(&who, GuildRole::Officer)? == guild_id,
+                    Error::<T>::NotInGuild
+                );
+            }
+            ensure!(
+                !LockedJokeymon::<T>::contains_key(jokeymon_id),
+                Error::<T>::JokeymonLocked
+            );
+
+            BorrowedJokeymon::<T>::remove(jokeymon_id);
+            GuildMembers::<T>::mutate(&borrower, |member| {
+                if let Some(member) = member {
+                    member.borrowed.saturating_dec();
+                }
+            });
+
+            Self::deposit_event(Event::JokeymonReturned {
+                guild_id,
+                jokeymon_id,
+                who: borrower,
+            });
+            Ok(().into())
+        }
+
+        /// Replace the caller's party with owned jokeymon, in battle order
+        ///
+        /// Locked jokeymon can be reordered but can't join or leave the party
+        #[pallet::call_index(32)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1 + 2 * T::MaxTeamSize::get() as u64, 1))]
+        pub fn set_party(origin: OriginFor<T>, party: Team<T>) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let mut account_data = AccountToData::<T>::get(&who);
+            for (index, jokeymon_id) in party.iter().enumerate() {
+                ensure!(
+                    !party[..index].contains(jokeymon_id),
+                    Error::<T>::InvalidTeam
+                );
+                ensure!(
+                    account_data.jokeymon.contains(jokeymon_id),
+                    Error::<T>::NotJokeymonOwner
+                );
+            }
+            // only jokeymon joining or leaving the party have to be unlocked
+            let moved = party
+                .iter()
+                .filter(|id| !account_data.party.contains(id))
+                .chain(account_data.party.iter().filter(|id| !party.contains(id)));
+            for jokeymon_id in moved {
+                Self::ensure_unlocked(*jokeymon_id)?;
+            }
+            account_data.party = party.clone();
+            AccountToData::<T>::insert(&who, account_data);
+
+            Self::deposit_event(Event::PartySet { who, party });
+            Ok(().into())
+        }
+
+        /// Move an unlocked jokeymon from the caller's party to their box
+        #[pallet::call_index(33)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn deposit_to_box(
+            origin: OriginFor<T>,
+            jokeymon_id: JokeymonId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let mut account_data = AccountToData::<T>::get(&who);
+            let index = account_data
+                .party
+                .iter()
+                .position(|id| *id == jokeymon_id)
+                .ok_or(Error::<T>::NotInParty)?;
+            Self::ensure_unlocked(jokeymon_id)?;
+            account_data.party.remove(index);
+            let party = account_data.party.clone();
+            AccountToData::<T>::insert(&who, account_data);
+
+            Self::deposit_event(Event::PartySet { who, party });
+            Ok(().into())
+        }
+
+        /// Move an unlocked jokeymon from the caller's box to the back of their party
+        #[pallet::call_index(34)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn withdraw_from_box(
+            origin: OriginFor<T>,
+            jokeymon_id: JokeymonId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let mut account_data = AccountToData::<T>::get(&who);
+            ensure!(
+                account_data.jokeymon.contains(&jokeymon_id),
+                Error::<T>::NotJokeymonOwner
+            );
+            ensure!(
+                !account_data.party.contains(&jokeymon_id),
+                Error::<T>::AlreadyInParty
+            );
+            Self::ensure_unlocked(jokeymon_id)?;
+            account_data
+                .party
+                .try_push(jokeymon_id)
+                .map_err(|_| Error::<T>::PartyFull)?;
+            let party = account_data.party.clone();
+            AccountToData::<T>::insert(&who, account_data);
+
+            Self::deposit_event(Event::PartySet { who, party });
+            Ok(().into())
+        }
+
+        /// Add a region to the world under the next region id
+        #[pallet::call_index(35)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn create_region(
+            origin: OriginFor<T>,
+            latitude: Coordinate,
+            longitude: Coordinate,
+            energy_yield: u32,
+            population_demographics: RegionPopulationDemographics<T>,
+        ) -> DispatchResultWithPostInfo {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(
+                population_demographics
+                    .keys()
+                    .all(SpeciesIdToSpeciesData::<T>::contains_key),
+                Error::<T>::UnknownSpecies
+            );
+            ensure!(
+                population_demographics
+                    .values()
+                    .all(|count| *count <= MAX_SPECIES_POPULATION),
+                Error::<T>::PopulationTooLarge
+            );
+
+            // chains started before genesis seeded the nonce can still have regions past it
+            let mut region_id = RegionNonce::<T>::get();
+            while RegionIdToRegion::<T>::contains_key(region_id) {
+                region_id = region_id.saturating_add(1);
+            }
+            RegionNonce::<T>::put(region_id.saturating_add(1));
+            let region = Region {
+                id: region_id,
+                total_population: population_demographics
+                    .values()
+                    .map(|count| *count as u64)
+                    .sum(),
+                population_demographics: population_demographics.clone(),
+                energy_yield,
+                latitude,
+                longitude,
+            };
+
+            Self::deposit_event(Event::RegionCreated {
+                region_id,
+                latitude,
+                longitude,
+                energy_yield,
+                population_demographics,
+            });
+            Self::refresh_protection(region_id, &region);
+            RegionIdToRegion::<T>::insert(region_id, region);
+            Ok(().into())
+        }
+
+        /// Add a species or replace its data
+        ///
+        /// The species it evolves to has to exist and must not evolve back into it, and a
+        /// herbivore has to eat something
+        #[pallet::call_index(36)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn set_species(
+            origin: OriginFor<T>,
+            species: JokeymonSpeciesData,
+        ) -> DispatchResultWithPostInfo {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(species.has_food_intake(), Error::<T>::NoFoodConsumption);
+            let mut next = species.evolves_to;
+            if let Some(target) = next {
+                ensure!(
+                    target == species.id || SpeciesIdToSpeciesData::<T>::contains_key(target),
+                    Error::<T>::UnknownSpecies
+                );
+            }
+            // a chain longer than the number of species has to revisit one
+            for _ in 0..=SpeciesIdToSpeciesData::<T>::iter_keys().count() {
+                let Some(current) = next else { break };
+                ensure!(current != species.id, Error::<T>::EvolutionCycle);
+                next = SpeciesIdToSpeciesData::<T>::get(current).evolves_to;
+            }
+
+            SpeciesIdToSpeciesData::<T>::insert(species.id, species.clone());
+            Self::deposit_event(Event::SpeciesSet { species });
+            Ok(().into())
+        }
+
+        /// Accept rated matches against any other account on the ladder
+        #[pallet::call_index(37)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn join_ladder(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                !LadderPlayers::<T>::contains_key(&who),
+                Error::<T>::AlreadyOnLadder
+            );
+            LadderPlayers::<T>::insert(&who, ());
+
+            Self::deposit_event(Event::LadderJoined { who });
+            Ok(().into())
+        }
+
+        /// Stop accepting rated matches, the account keeps its rating
+        #[pallet::call_index(38)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn leave_ladder(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            LadderPlayers::<T>::take(&who).ok_or(Error::<T>::NotOnLadder)?;
+
+            Self::deposit_event(Event::LadderLeft { who });
+            Ok(().into())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// the pot account fees are paid into
+        pub fn account_id() -> T::AccountId {
+            T::PalletId::get().into_account_truncating()
+        }
+
+        /// check the ecosystem and ownership invariants
+        ///
+        /// - every region's total population is the sum of its demographics
+        /// - every jokeymon an account holds has data and is held by no other account
+        /// - every party is made of distinct jokeymon the account holds
+        /// - the jokeymon id nonce is past every existing jokeymon id
+        #[cfg(any(feature = "try-runtime", test))]
+        pub fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+            use scale_info::prelude::collections::BTreeSet;
+
+            for region in RegionIdToRegion::<T>::iter_values() {
+                let sum: u64 = region
+                    .population_demographics
+                    .values()
+                    .map(|count| *count as u64)
+                    .sum();
+                ensure!(
+                    region.total_population == sum,
+                    "region total population does not match its demographics"
+                );
+            }
+
+            let mut held = BTreeSet::new();
+            for (who, data) in AccountToData::<T>::iter() {
+                for jokeymon_id in data.jokeymon.iter() {
+                    ensure!(
+                        JokeymonIdToData::<T>::contains_key(jokeymon_id),
+                        "held jokeymon has no data"
+                    );
+                    ensure!(held.insert(*jokeymon_id), "jokeymon is held twice");
+                    ensure!(
+                        JokeymonIdToOwner::<T>::get(jokeymon_id).as_ref() == Some(&who),
+                        "held jokeymon has a different owner"
+                    );
+                }
+                for (index, jokeymon_id) in data.party.iter().enumerate() {
+                    ensure!(
+                        data.jokeymon.contains(jokeymon_id)
+                            && !data.party[..index].contains(jokeymon_id),
+                        "party jokeymon is not held or is in the party twice"
+                    );
+                }
+            }
+
+            let nonce = JokeymonIdNonce::<T>::get();
+            ensure!(
+                JokeymonIdToData::<T>::iter_keys().all(|jokeymon_id| jokeymon_id < nonce),
+                "jokeymon id nonce is not past every jokeymon id"
+            );
+            Ok(())
+        }
+
+        /// the account holding a guild's pot and roster
+        pub fn guild_account_id(guild_id: GuildId) -> T::AccountId {
+            T::PalletId::get().into_sub_account_truncating((b"guild", guild_id))
+        }
+
+        /// check `who` is in a guild with at least `role`, returning the guild
+        pub(super) fn ensure_guild_role(
+            who: &T::AccountId,
+            role: GuildRole,
+        ) -> Result<GuildId, Error<T>> {
+            let member = GuildMembers::<T>::get(who).ok_or(Error::<T>::NotInGuild)?;
+            ensure!(member.role >= role, Error::<T>::InsufficientGuildRole);
+            Ok(member.guild_id)
+        }
+
+        /// check a jokeymon is held in a guild's roster
+        pub(super) fn ensure_in_roster(
+            guild_id: GuildId,
+            jokeymon_id: JokeymonId,
+        ) -> DispatchResult {
+            ensure!(
+                JokeymonIdToOwner::<T>::get(jokeymon_id) == Some(Self::guild_account_id(guild_id)),
+                Error::<T>::NotInRoster
+            );
+            Ok(())
+        }
+
+        /// pay a fee from an account into the pot
+        pub(super) fn charge_fee(who: &T::AccountId, fee: BalanceOf<T>) -> DispatchResult {
+            if fee.is_zero() {
+                return Ok(());
+            }
+            T::Currency::transfer(who, &Self::account_id(), fee, Preservation::Preserve)?;
+            Ok(())
+        }
+
+        /// check the seller of a listed or auctioned jokeymon still owns it before paying them
+        pub(super) fn ensure_still_owned(
+            jokeymon_id: JokeymonId,
+            seller: &T::AccountId,
+        ) -> DispatchResult {
+            ensure!(
+                JokeymonIdToOwner::<T>::get(jokeymon_id).as_ref() == Some(seller),
+                Error::<T>::NotJokeymonOwner
+            );
+            Ok(())
+        }
+
+        /// pay for a sold jokeymon, the marketplace fee goes to the treasury
+        pub(super) fn pay_for_sale(
+            buyer: &T::AccountId,
+            seller: &T::AccountId,
+            price: BalanceOf<T>,
+        ) -> Result<BalanceOf<T>, DispatchError> {
+            let fee = T::MarketplaceFee::get().mul_floor(price);
+            if !fee.is_zero() {
+                T::Currency::transfer(
+                    buyer,
+                    &T::TreasuryAccount::get(),
+                    fee,
+                    Preservation::Preserve,
+                )?;
+            }
+            T::Currency::transfer(
+                buyer,
+                seller,
+                price.saturating_sub(fee),
+                Preservation::Preserve,
+            )?;
+            Ok(fee)
+        }
+
+        /// the current price of a dutch auction, decaying linearly towards the floor
+        pub(super) fn dutch_auction_price(
+            auction: &Auction<T>,
+            now: BlockNumberFor<T>,
+        ) -> BalanceOf<T> {
+            match auction.kind {
+                AuctionKind::Dutch {
+                    start_price,
+                    floor_price,
+                } => {
+                    let elapsed = now.saturating_sub(auction.start);
+                    let duration = auction.end.saturating_sub(auction.start);
+                    let decayed = Perbill::from_rational(elapsed, duration)
+                        .mul_floor(start_price.saturating_sub(floor_price));
+                    start_price.saturating_sub(decayed)
+                }
+                AuctionKind::English { min_bid } => min_bid,
+            }
+        }
+
+        /// weight of settling a single auction in `on_idle`
+        fn auction_settlement_weight() -> Weight {
+            Weight::from_parts(10_000, 0).saturating_add(T::DbWeight::get().reads_writes(6, 8))
+        }
+
+        /// settle auctions that have ended, as far as the weight limit allows
+        pub(super) fn settle_expired_auctions(now: BlockNumberFor<T>, limit: Weight) -> Weight {
+            let db_weight = T::DbWeight::get();
+            let mut used = db_weight.reads(1);
+            if used.any_gt(limit) {
+                return Weight::zero();
+            }
+
+            let mut queue = AuctionQueue::<T>::get().into_inner();
+            let mut settled = 0;
+            for (end, jokeymon_id) in queue.iter() {
+                let next = used
+                    .saturating_add(Self::auction_settlement_weight())
+                    .saturating_add(db_weight.writes(1));
+                if *end > now || next.any_gt(limit) {
+                    break;
+                }
+                Self::settle_auction(*jokeymon_id);
+                used.saturating_accrue(Self::auction_settlement_weight());
+                settled += 1;
+            }
+
+            if settled > 0 {
+                queue.drain(..settled);
+                AuctionQueue::<T>::put(BoundedVec::truncate_from(queue));
+                used.saturating_accrue(db_weight.writes(1));
+            }
+            used
+        }
+
+        /// end an auction, selling to the highest bidder if there is one
+        ///
+        /// If the sale cannot go through the bid is refunded and the seller keeps the jokeymon
+        pub(super) fn settle_auction(jokeymon_id: JokeymonId) {
+            let Some(auction) = Auctions::<T>::take(jokeymon_id) else {
+                return;
+            };
+            Self::unlock_jokeymon(jokeymon_id, LockReason::Auction);
+
+            if let Some((bidder, amount)) = auction.highest_bid {
+                let reason = HoldReason::AuctionBid.into();
+                let sale = with_storage_layer(|| -> Result<BalanceOf<T>, DispatchError> {
+                    T::Currency::release(&reason, &bidder, amount, Precision::Exact)?;
+                    Self::ensure_still_owned(jokeymon_id, &auction.seller)?;
+                    let fee = Self::pay_for_sale(&bidder, &auction.seller, amount)?;
+                    Self::do_transfer(jokeymon_id, &bidder)?;
+                    Ok(fee)
+                });
+                match sale {
+                    Ok(fee) => {
+                        Self::deposit_event(Event::AuctionSettled {
+                            jokeymon_id,
+                            seller: auction.seller,
+                            buyer: bidder,
+                            price: amount,
+                            fee,
+                        });
+                        return;
+                    }
+                    Err(_) => {
+                        let _ =
+                            T::Currency::release(&reason, &bidder, amount, Precision::BestEffort);
+                        Self::deposit_event(Event::BidRefunded {
+                            jokeymon_id,
+                            bidder,
+                            amount,
+                        });
+                    }
+                }
+            }
+
+            Self::deposit_event(Event::AuctionUnsold {
+                jokeymon_id,
+                seller: auction.seller,
+            });
+        }
+
+        /// put an account's new rating in its place on the leaderboard
+        pub(super) fn update_leaderboard(who: &T::AccountId, rating: Rating) {
+            Leaderboard::<T>::mutate(|board| {
+                board.retain(|(account, _)| account != who);
+                Self::insert_standing(board, who.clone(), rating);
+            });
+        }
+
+        /// insert a rating below every equal or higher one, dropping the lowest if full
+        fn insert_standing(standings: &mut Standings<T>, who: T::AccountId, rating: Rating) {
+            let index = standings.partition_point(|(_, other)| *other >= rating);
+            let _ = standings.force_insert_keep_left(index, (who, rating));
+        }
+
+        /// a page of the current leaderboard, highest rated first
+        pub fn leaderboard_page(page: u32, page_size: u32) -> Vec<(T::AccountId, Rating)> {
+            Leaderboard::<T>::get()
+                .into_iter()
+                .skip(page.saturating_mul(page_size) as usize)
+                .take(page_size as usize)
+                .collect()
+        }
+
+        /// close the current season if `n` is a season boundary
+        ///
+        /// The leaderboard is stored as the season's standings and the season reward is split
+        /// evenly across it
+        pub(super) fn end_season_if_due(n: BlockNumberFor<T>) -> Weight {
+            let db_weight = T::DbWeight::get();
+            let length = T::SeasonLength::get();
+            if length.is_zero() || n.is_zero() || !(n % length).is_zero() {
+                return Weight::zero();
+            }
+
+            let season = CurrentSeason::<T>::get();
+            let standings = Leaderboard::<T>::take();
+            let share = match standings.len() as u32 {
+                0 => Zero::zero(),
+                count => T::SeasonReward::get() / count.into(),
+            };
+            if !share.is_zero() {
+                for (who, _) in standings.iter() {
+                    let paid = T::Currency::transfer(
+                        &Self::account_id(),
+                        who,
+                        share,
+                        Preservation::Preserve,
+                    );
+                    if paid.is_ok() {
+                        Self::deposit_event(Event::SeasonRewardPaid {
+                            season,
+                            who: who.clone(),
+                            amount: share,
+                        });
+                    }
+                }
+            }
+
+            let rewarded = standings.len() as u64;
+            SeasonStandings::<T>::insert(season, standings);
+            CurrentSeason::<T>::put(season.saturating_add(1));
+            Self::deposit_event(Event::SeasonEnded { season });
+
+            db_weight.reads_writes(2 + rewarded * 2, 3 + rewarded * 2)
+        }
+
+        /// check a team is non empty, has no duplicates and is made of `who`'s party and
+        /// jokeymon it borrowed
+        pub(super) fn ensure_valid_team(who: &T::AccountId, team: &Team<T>) -> DispatchResult {
+            ensure!(!team.is_empty(), Error::<T>::InvalidTeam);
+            let party = AccountToData::<T>::get(who).party;
+            for (index, jokeymon_id) in team.iter().enumerate() {
+                ensure!(
+                    !team[..index].contains(jokeymon_id),
+                    Error::<T>::InvalidTeam
+                );
+                ensure!(
+                    party.contains(jokeymon_id)
+                        || BorrowedJokeymon::<T>::get(jokeymon_id).as_ref() == Some(who),
+                    Error::<T>::InvalidTeam
+                );
+            }
+            Ok(())
+        }
+
+        /// battle stats of every jokeymon in a team, in order
+        pub(super) fn team_fighters(team: &[JokeymonId]) -> Vec<Fighter> {
+            team.iter()
+                .filter_map(|id| Some((JokeymonIdToData::<T>::get(id)?, Experience::<T>::get(id))))
+                .map(|(data, experience)| {
+                    Fighter::from_species(&SpeciesIdToSpeciesData::<T>::get(data.id))
+                        .with_experience(experience)
+                })
+                .collect()
+        }
+
+        /// split the battle experience evenly across the party of the winner of a battle
+        pub(super) fn share_experience(who: &T::AccountId) {
+            let party = AccountToData::<T>::get(who).party;
+            let amount = T::BattleExperience::get()
+                .checked_div(party.len() as u32)
+                .unwrap_or_default();
+            if amount == 0 {
+                return;
+            }
+            for jokeymon_id in party.iter() {
+                Experience::<T>::mutate(jokeymon_id, |experience| {
+                    *experience = experience.saturating_add(amount)
+                });
+            }
+            Self::deposit_event(Event::ExperienceShared {
+                who: who.clone(),
+                party,
+                amount,
+            });
+        }
+
+        /// a deterministic stream of rolls for a battle, seeded from shared randomness
+        pub(super) fn battle_rolls() -> impl FnMut(u32) -> u8 {
+            let seed = Self::get_and_increment_random_nonce();
+            let (random, _) = T::RandomSource::random(&seed);
+            move |index| T::Hashing::hash_of(&(&random, index)).as_ref()[0]
+        }
+
+        /// create a new jokeymon of a species owned by `who`, joining the party if it has room
+        ///
+        /// The caller writes back `account_data` and holds the deposit
+        pub(super) fn mint_jokeymon(
+            who: &T::AccountId,
+            account_data: &mut AccountData<T>,
+            species_id: JokeymonSpeciesId,
+        ) -> Result<JokeymonId, Error<T>> {
+            let jokeymon_id = Self::get_and_increment_jokeymon_id_nonce();
+            account_data
+                .jokeymon
+                .try_push(jokeymon_id)
+                .map_err(|_| Error::<T>::TooManyJokeymon)?;
+            let _ = account_data.party.try_push(jokeymon_id);
+
+            let data = JokeymonData::<T> {
+                id: species_id,
+                birth_date: SystemPallet::<T>::block_number(),
+            };
+            JokeymonIdToData::<T>::insert(jokeymon_id, data);
+            JokeymonIdToOwner::<T>::insert(jokeymon_id, who);
+            Ok(jokeymon_id)
+        }
+
+        /// advance every open quest a catch counts towards
+        pub(super) fn progress_quests(
+            who: &T::AccountId,
+            species_id: JokeymonSpeciesId,
+            region_id: RegionId,
+        ) {
+            let diet = SpeciesIdToSpeciesData::<T>::get(species_id).diet;
+            for quest_id in OpenQuests::<T>::get() {
+                let Some(quest) = Quests::<T>::get(quest_id) else {
+                    continue;
+                };
+                let QuestObjective::Catch {
+                    species_id: wanted_species,
+                    diet: wanted_diet,
+                    region_id: wanted_region,
+                    count,
+                } = quest.objective;
+                if wanted_species.is_some_and(|id| id != species_id)
+                    || wanted_diet.is_some_and(|d| d != diet)
+                    || wanted_region.is_some_and(|id| id != region_id)
+                {
+                    continue;
+                }
+
+                QuestProgresses::<T>::mutate(who, quest_id, |progress| {
+                    if progress.count < count {
+                        progress.count += 1;
+                        if progress.count == count {
+                            Self::deposit_event(Event::QuestCompleted {
+                                who: who.clone(),
+                                quest_id,
+                            });
+                        }
+                    }
+                });
+            }
+        }
+
+        /// pay a gym's controller for the blocks since they were last paid
+        ///
+        /// Pays what the pot can afford, the rest is forfeit
+        pub(super) fn pay_gym_reward(region_id: RegionId, gym: &mut Gym<T>) {
+            let now = SystemPallet::<T>::block_number();
+            let blocks: u32 = now.saturating_sub(gym.paid_until).saturated_into();
+            gym.paid_until = now;
+            let Some(who) = gym.controller.as_ref() else {
+                return;
+            };
+
+            let pot = Self::account_id();
+            let available = <T::Currency as fungible::Inspect<_>>::reducible_balance(
+                &pot,
+                Preservation::Preserve,
+                Fortitude::Polite,
+            );
+            let amount = T::GymReward::get()
+                .saturating_mul(blocks.into())
+                .min(available);
+            if amount.is_zero() {
+                return;
+            }
+            if T::Currency::transfer(&pot, who, amount, Preservation::Preserve).is_ok() {
+                Self::deposit_event(Event::GymRewardPaid {
+                    region_id,
+                    who: who.clone(),
+                    amount,
+                });
+            }
+        }
+
+        /// mark the species of every jokeymon in `team` as seen by `who`
+        pub(super) fn record_seen(who: &T::AccountId, team: &[JokeymonId]) {
+            Pokedexes::<T>::mutate(who, |pokedex| {
+                for data in team.iter().filter_map(JokeymonIdToData::<T>::get) {
+                    pokedex.see(data.id);
+                }
+            });
+        }
+
+        /// mark a species as caught by `who`, paying the milestone reward if one is reached
+        pub(super) fn record_caught(who: &T::AccountId, species_id: JokeymonSpeciesId) {
+            let mut pokedex = Pokedexes::<T>::get(who);
+            let new = pokedex.catch(species_id);
+            let caught = pokedex.caught_count();
+            Pokedexes::<T>::insert(who, pokedex);
+
+            let milestone = T::PokedexMilestone::get();
+            if !new || milestone == 0 || !caught.is_multiple_of(milestone) {
+                return;
+            }
+            let reward = T::PokedexReward::get();
+            let paid =
+                T::Currency::transfer(&Self::account_id(), who, reward, Preservation::Preserve);
+            Self::deposit_event(Event::PokedexMilestoneReached {
+                who: who.clone(),
+                caught,
+                reward: if paid.is_ok() { reward } else { Zero::zero() },
+            });
+        }
+
+        /// share of the species living in each region that `who` has caught
+        pub fn pokedex_completion(who: &T::AccountId) -> Vec<(RegionId, Permill)> {
+            let pokedex = Pokedexes::<T>::get(who);
+            let mut completion: Vec<_> = RegionIdToRegion::<T>::iter()
+                .map(|(region_id, region)| {
+                    (
+                        region_id,
+                        pokedex.completion(region.population_demographics.keys()),
+                    )
+                })
+                .collect();
+            completion.sort_by_key(|(region_id, _)| *region_id);
+            completion
+        }
+
+        /// lock a jokeymon for `reason`, the caller checks it isn't locked already
+        pub(super) fn lock_jokeymon(jokeymon_id: JokeymonId, reason: LockReason) {
+            LockedJokeymon::<T>::insert(jokeymon_id, reason);
+        }
+
+        /// unlock a jokeymon if it is locked for `reason`, leaving any other lock in place
+        pub(super) fn unlock_jokeymon(jokeymon_id: JokeymonId, reason: LockReason) {
+            if LockedJokeymon::<T>::get(jokeymon_id) == Some(reason) {
+                LockedJokeymon::<T>::remove(jokeymon_id);
+            }
+        }
+
+        /// fail with `JokeymonLocked` while anything holds a lock on the jokeymon
+        pub(super) fn ensure_unlocked(jokeymon_id: JokeymonId) -> DispatchResult {
+            ensure!(
+                !LockedJokeymon::<T>::contains_key(jokeymon_id),
+                Error::<T>::JokeymonLocked
+            );
+            Ok(())
+        }
+
+        /// move a jokeymon and its deposit from its owner to `dest`
+        pub(super) fn do_transfer(jokeymon_id: JokeymonId, dest: &T::AccountId) -> DispatchResult {
+            let from =
+                JokeymonIdToOwner::<T>::get(jokeymon_id).ok_or(Error::<T>::UnknownJokeymon)?;
+            Self::ensure_unlocked(jokeymon_id)?;
+            if from == *dest {
+                return Ok(());
+            }
+
+            // move the jokeymon between collections
+            let mut from_data = AccountToData::<T>::get(&from);
+            let mut dest_data = AccountToData::<T>::get(dest);
+            from_data.jokeymon.retain(|id| *id != jokeymon_id);
+            from_data.party.retain(|id| *id != jokeymon_id);
+            dest_data
+                .jokeymon
+                .try_push(jokeymon_id)
+                .map_err(|_| Error::<T>::TooManyJokeymon)?;
+
+            // the receiver now holds the deposit
+            let reason = HoldReason::JokeymonDeposit.into();
+            T::Currency::hold(&reason, dest, T::JokeymonDeposit::get())?;
+            T::Currency::release(
+                &reason,
+                &from,
+                T::JokeymonDeposit::get(),
+                Precision::BestEffort,
+            )?;
+
+            AccountToData::<T>::set(&from, from_data);
+            AccountToData::<T>::set(dest, dest_data);
+            JokeymonIdToOwner::<T>::insert(jokeymon_id, dest);
+
+            Self::deposit_event(Event::JokeymonTransferred {
+                jokeymon_id,
+                from,
+                to: dest.clone(),
+            });
+            Ok(())
+        }
+
+        /// use and update the random nonce
+        pub(super) fn get_and_increment_random_nonce() -> Vec<u8> {
+            let val = RandomNonce::<T>::get();
+            RandomNonce::<T>::put(val.wrapping_add(1));
+            val.encode()
+        }
+
+        /// use and update the jokeymon unique identifier nonce
+        pub(super) fn get_and_increment_jokeymon_id_nonce() -> JokeymonId {
+            let val = JokeymonIdNonce::<T>::get();
+            JokeymonIdNonce::<T>::put(val.wrapping_add(1));
+            val
+        }
+
+        /// get a random number given the nonce
+        pub(super) fn get_random_number(seed: &[u8]) -> u64 {
+            let (random, _) = T::RandomSource::random(seed);
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&random.encode()[..8]);
+            u64::from_le_bytes(bytes)
+        }
+
+        /// get a jokeymon in a region, given a random number
+        ///
+        /// Each species is picked with probability proportional to its catch weight
+        pub fn get_jokeymon_in_region(
+            region: &Region<T>,
+            roll: u64,
+        ) -> Result<JokeymonSpeciesId, Error<T>> {
+            Self::pick_weighted(&Self::catch_weights(region), roll)
+                .ok_or(Error::<T>::NoCatchableJokeymon)
+        }
+
+        /// pick an id given a random number, each with probability exactly proportional to its
+        /// weight
+        pub(super) fn pick_weighted(
+            weights: &[(JokeymonSpeciesId, u64)],
+            roll: u64,
+        ) -> Option<JokeymonSpeciesId> {
+            let total: u64 = weights.iter().map(|(_, weight)| *weight).sum();
+            if total == 0 {
+                return None;
+            }
+
+            // walk the cumulative weights until the roll falls inside one
+            let mut target = roll % total;
+            for (id, weight) in weights {
+                if target < *weight {
+                    return Some(*id);
+                }
+                target -= *weight;
+            }
+            None
+        }
+
+        /// the herbivores a region's energy can feed, its energy yield over the average intake
+        /// of the herbivore species living there
+        pub(super) fn herbivore_capacity(energy_yield: u32, intake: u64, herbivores: u64) -> u64 {
+            // herbivores that eat nothing are rejected by the admin calls but must never divide
+            // by zero here
+            let average = intake
+                .checked_div(herbivores)
+                .filter(|average| *average > 0);
+            energy_yield as u64 / average.unwrap_or(1)
+        }
+
+        /// the carrying capacity of each species in a region, its share of the region's
+        /// equilibrium under the on-chain population model
+        ///
+        /// Herbivores share the herbivore equilibrium and carnivores the carnivore one, a
+        /// species with no capacity can't be sustained there.
+        pub fn carrying_capacities(region: &Region<T>) -> Vec<(JokeymonSpeciesId, u64)> {
+            let diets: Vec<_> = region
+                .population_demographics
+                .keys()
+                .map(|id| {
+                    let data = SpeciesIdToSpeciesData::<T>::get(id);
+                    (*id, data.diet, data.avg_daily_food_consumption as u64)
+                })
+                .collect();
+            let herbivores = diets
+                .iter()
+                .filter(|(_, diet, _)| *diet == Diet::Herbivore)
+                .count() as u64;
+            let carnivores = diets.len() as u64 - herbivores;
+            let intake = diets
+                .iter()
+                .filter(|(_, diet, _)| *diet == Diet::Herbivore)
+                .map(|(_, _, intake)| intake)
+                .sum();
+
+            let capacity = Self::herbivore_capacity(region.energy_yield, intake, herbivores);
+            let (herbivore_total, carnivore_total) = PopulationModel::DEFAULT.equilibrium(capacity);
+            diets
+                .into_iter()
+                .map(|(id, diet, _)| {
+                    let capacity = match diet {
+                        Diet::Herbivore => herbivore_total / herbivores,
+                        Diet::Carnivore => carnivore_total / carnivores,
+                    };
+                    (id, capacity)
+                })
+                .collect()
+        }
+
+        /// how close a count is to a carrying capacity, one at or above it or without one
+        fn pressure(count: JokeymonCount, capacity: u64) -> Permill {
+            match capacity {
+                0 => Permill::one(),
+                capacity => Permill::from_rational((count as u64).min(capacity), capacity),
+            }
+        }
+
+        /// how close a species is to its carrying capacity in a region, one at or above it
+        pub fn population_pressure(region: &Region<T>, species_id: JokeymonSpeciesId) -> Permill {
+            let count = region
+                .population_demographics
+                .get(&species_id)
+                .copied()
+                .unwrap_or_default();
+            let capacity = Self::carrying_capacities(region)
+                .into_iter()
+                .find(|(id, _)| *id == species_id)
+                .map_or(0, |(_, capacity)| capacity);
+            Self::pressure(count, capacity)
+        }
+
+        /// whether a species is too far below its carrying capacity to be caught in a region
+        pub fn is_protected(region: &Region<T>, species_id: JokeymonSpeciesId) -> bool {
+            Self::population_pressure(region, species_id) < T::ProtectionThreshold::get()
+        }
+
+        /// the weight each species of a region is caught with
+        ///
+        /// A species' population is scaled by its population pressure, so species far below
+        /// their carrying capacity are caught less often, and protected species not at all.
+        pub(super) fn catch_weights(region: &Region<T>) -> Vec<(JokeymonSpeciesId, u64)> {
+            Self::carrying_capacities(region)
+                .into_iter()
+                .map(|(id, capacity)| {
+                    let count = region.population_demographics[&id];
+                    let pressure = Self::pressure(count, capacity);
+                    let weight = if pressure < T::ProtectionThreshold::get() {
+                        0
+                    } else {
+                        count as u64 * pressure.deconstruct() as u64
+                    };
+                    (id, weight)
+                })
+                .collect()
+        }
+
+        /// bring `ProtectedSpecies` in line with a region's populations, announcing every change
+        pub(super) fn refresh_protection(region_id: RegionId, region: &Region<T>) {
+            for (species_id, capacity) in Self::carrying_capacities(region) {
+                let pressure =
+                    Self::pressure(region.population_demographics[&species_id], capacity);
+                let protected = pressure < T::ProtectionThreshold::get();
+                if protected == ProtectedSpecies::<T>::contains_key(region_id, species_id) {
+                    continue;
+                }
+                if protected {
+                    ProtectedSpecies::<T>::insert(region_id, species_id, ());
+                    Self::deposit_event(Event::SpeciesProtected {
+                        region_id,
+                        species_id,
+                    });
+                } else {
+                    ProtectedSpecies::<T>::remove(region_id, species_id);
+                    Self::deposit_event(Event::SpeciesUnprotected {
+                        region_id,
+                        species_id,
+                    });
+                }
+            }
+
+            // species that left the region are no longer protected there
+            let gone: Vec<_> = ProtectedSpecies::<T>::iter_key_prefix(region_id)
+                .filter(|species_id| !region.population_demographics.contains_key(species_id))
+                .collect();
+            for species_id in gone {
+                ProtectedSpecies::<T>::remove(region_id, species_id);
+                Self::deposit_event(Event::SpeciesUnprotected {
+                    region_id,
+                    species_id,
+                });
             }
-            u32::MAX.into()
         }
 
         /// Decrements the population size of a jokeymon in a region
@@ -308,7 +3158,6 @@ pub mod pallet {
         }
 
         /// Increments the population size of a jokeymon in a region
-        #[allow(dead_code)]
         pub(super) fn increment_species_in_population(
             region: &mut Region<T>,
             id: JokeymonSpeciesId,
@@ -331,57 +3180,239 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Update the population of every region, done once a block
+        ///
+        /// Every region that changed is stored with a `RegionPopulationUpdated` event, and its
+        /// protected species are refreshed. A region that didn't change is left as it is.
+        /// Returns the weight used, at worst every species of a changed region changes
+        /// protection.
+        pub fn update_populations(model: &PopulationModel) -> Weight {
+            let mut regions = 0;
+            let mut reads = 0;
+            let mut writes = 0;
+            // For each region, update it's population
+            for (region_id, mut region) in RegionIdToRegion::<T>::iter() {
+                let species = region.population_demographics.len() as u64;
+                regions += 1;
+                // the region and each species' data
+                reads += 1 + species;
+                let before = region.population_demographics.clone();
+                Self::update_regional_population(&mut region, model);
+                let changes = Self::population_changes(&before, &region.population_demographics);
+                if changes.is_empty() {
+                    continue;
+                }
+                // each species' data again and protection, and the protected species
+                reads += 1 + 2 * species;
+                // the region and its event, each species' protection and its event
+                writes += 2 + 2 * species;
+                Self::deposit_event(Event::RegionPopulationUpdated { region_id, changes });
+                Self::refresh_protection(region_id, &region);
+                RegionIdToRegion::<T>::insert(region_id, region);
+            }
+            Weight::from_parts(10_000, 0)
+                .saturating_mul(regions)
+                .saturating_add(T::DbWeight::get().reads_writes(reads, writes))
+        }
+
+        /// an account's catch stamina at the current block
+        ///
+        /// `updated` is moved forward only by whole regeneration periods, so a catch doesn't
+        /// lose progress towards the next point
+        pub fn catch_stamina(who: &T::AccountId) -> CatchStamina<T> {
+            let now = SystemPallet::<T>::block_number();
+            let max = T::MaxCatchStamina::get();
+            let regen = T::CatchStaminaRegen::get();
+            let full = CatchStamina {
+                points: max,
+                updated: now,
+            };
+            let Some(stamina) = Stamina::<T>::get(who) else {
+                return full;
+            };
+            if regen.is_zero() {
+                return full;
+            }
+
+            let periods = now.saturating_sub(stamina.updated) / regen;
+            let points = stamina
+                .points
+                .saturating_add(periods.saturated_into::<u32>());
+            if points >= max {
+                return full;
+            }
+            CatchStamina {
+                points,
+                updated: stamina
+                    .updated
+                    .saturating_add(periods.saturating_mul(regen)),
+            }
+        }
+
+        /// add a snapshot of every region to its history every `PopulationSampleInterval` blocks
+        ///
+        /// At most `MaxRegionsSampledPerBlock` regions are sampled in a block, the sample carries
+        /// on from the last region in the blocks after. A sample that is still going when the
+        /// next one is due is cut short.
+        pub(super) fn sample_populations_if_due(n: BlockNumberFor<T>) -> Weight {
+            let db_weight = T::DbWeight::get();
+            let interval = T::PopulationSampleInterval::get();
+            let capacity = T::PopulationHistoryLength::get();
+            if interval.is_zero() || capacity == 0 {
+                return Weight::zero();
+            }
+            let cursor_proof = Weight::from_parts(0, RegionId::max_encoded_len() as u64);
+
+            let regions = if (n % interval).is_zero() {
+                RegionIdToRegion::<T>::iter()
+            } else {
+                match PopulationSampleCursor::<T>::get() {
+                    Some(last) => RegionIdToRegion::<T>::iter_from(
+                        RegionIdToRegion::<T>::hashed_key_for(last),
+                    ),
+                    None => return db_weight.reads(1).saturating_add(cursor_proof),
+                }
+            };
+
+            let max = T::MaxRegionsSampledPerBlock::get();
+            let mut sampled = 0u32;
+            let mut last = None;
+            for (region_id, region) in regions.take(max as usize) {
+                let head = PopulationHistoryHead::<T>::get(region_id);
+                PopulationHistory::<T>::insert(
+                    region_id,
+                    head,
+                    PopulationSnapshot {
+                        block: n,
+                        population_demographics: region.population_demographics,
+                    },
+                );
+                PopulationHistoryHead::<T>::insert(region_id, (head + 1) % capacity);
+                sampled += 1;
+                last = Some(region_id);
+            }
+            // a full batch may have left regions for the next block
+            match last {
+                Some(region_id) if sampled == max => PopulationSampleCursor::<T>::put(region_id),
+                _ => PopulationSampleCursor::<T>::kill(),
+            }
+
+            // every region read is in the proof with its head
+            let region_proof = Region::<T>::max_encoded_len() + u32::max_encoded_len();
+            let sampled = sampled as u64;
+            db_weight
+                .reads_writes(1 + 2 * sampled, 1 + 2 * sampled)
+                .saturating_add(cursor_proof)
+                .saturating_add(Weight::from_parts(0, sampled * region_proof as u64))
+        }
+
+        /// the sampled populations of a region, oldest first
+        pub fn population_history(
+            region_id: RegionId,
+        ) -> Vec<(
+            BlockNumberFor<T>,
+            BTreeMap<JokeymonSpeciesId, JokeymonCount>,
+        )> {
+            let capacity = T::PopulationHistoryLength::get();
+            let head = PopulationHistoryHead::<T>::get(region_id);
+            (head..capacity)
+                .chain(0..head)
+                .filter_map(|slot| PopulationHistory::<T>::get(region_id, slot))
+                .map(|snapshot| {
+                    (
+                        snapshot.block,
+                        snapshot.population_demographics.into_inner(),
+                    )
+                })
+                .collect()
+        }
+
+        /// the change in count of every species whose count differs between the demographics
+        fn population_changes(
+            before: &RegionPopulationDemographics<T>,
+            after: &RegionPopulationDemographics<T>,
+        ) -> BoundedVec<(JokeymonSpeciesId, i64), T::MaxSpeciesInRegion> {
+            let mut changes = BoundedVec::new();
+            for (id, count) in after.iter() {
+                let previous = before.get(id).copied().unwrap_or_default();
+                if *count != previous {
+                    // a region never holds more species than the bound
+                    let _ = changes.try_push((*id, *count as i64 - previous as i64));
+                }
+            }
+            changes
+        }
+
         /// Updates a regions population based on the Lotka-Volterra formula
-        pub(super) fn update_regional_population(region: &mut Region<T>) {
+        pub fn update_regional_population(region: &mut Region<T>, model: &PopulationModel) {
             // Get number of herbivores and carnivores
-            let mut herb_species_count = 0;
-            let mut carn_species_count = 0;
-            let mut herb_total_count = 0;
-            let mut carn_total_count = 0;
-            let mut avg_herb_food_intake = 0;
+            let mut herb_species_count: i64 = 0;
+            let mut carn_species_count: i64 = 0;
+            let mut herb_total_count: i64 = 0;
+            let mut carn_total_count: i64 = 0;
+            let mut avg_herb_food_intake: i64 = 0;
 
             for (id, pop) in &region.population_demographics {
                 let data = SpeciesIdToSpeciesData::<T>::get(id);
                 match data.diet {
                     Diet::Herbivore => {
-                        herb_total_count += *pop as i32;
-                        avg_herb_food_intake += data.avg_daily_food_consumption as i32;
+                        herb_total_count += *pop as i64;
+                        avg_herb_food_intake += data.avg_daily_food_consumption as i64;
                         herb_species_count += 1;
                     }
                     Diet::Carnivore => {
-                        carn_total_count += *pop as i32;
+                        carn_total_count += *pop as i64;
                         carn_species_count += 1;
                     }
                 }
             }
 
             // Calculate carrying capacity (if herbivores exist)
-            avg_herb_food_intake = avg_herb_food_intake
-                .checked_div(herb_species_count)
-                .unwrap_or(1);
-            let carrying_capacity = region.energy_yield / (avg_herb_food_intake as u32);
+            let carrying_capacity = Self::herbivore_capacity(
+                region.energy_yield,
+                avg_herb_food_intake as u64,
+                herb_species_count as u64,
+            );
 
-            // Mock params
-            let timestep = 10_000i32;
-            let (alpha, beta, delta, gamma) = (200i32, 1i32, 1i32, 200i32);
+            let PopulationModel {
+                timestep,
+                alpha,
+                beta,
+                delta,
+                gamma,
+            } = *model;
+            let (timestep, alpha, beta, delta, gamma) = (
+                timestep as i64,
+                alpha as i64,
+                beta as i64,
+                delta as i64,
+                gamma as i64,
+            );
 
             // Calculate growth or decay of each Lotka-Volterra (https://chatgpt.com/share/6707ea69-5cfc-8003-b153-20f2540b34fc)
+            // saturating, so no population or coefficient can overflow and halt the chain
             let carry_term =
-                Permill::one() - Permill::from_rational(herb_total_count as u32, carrying_capacity); // issue here where it can't be negative
-            let first_term_dh = carry_term.mul_ceil((alpha * herb_total_count) as u32);
-            let dh: i32 = (first_term_dh as i32) - (beta * herb_total_count * carn_total_count);
+                Permill::one() - Permill::from_rational(herb_total_count as u64, carrying_capacity); // issue here where it can't be negative
+            let first_term_dh =
+                carry_term.mul_ceil(alpha.saturating_mul(herb_total_count).max(0) as u64) as i64;
+            let dh = first_term_dh.saturating_sub(
+                beta.saturating_mul(herb_total_count)
+                    .saturating_mul(carn_total_count),
+            );
 
-            let first_term_dc = delta * herb_total_count * carn_total_count;
-            let dc: i32 = (first_term_dc as i32) - (gamma * carn_total_count);
+            let first_term_dc = delta
+                .saturating_mul(herb_total_count)
+                .saturating_mul(carn_total_count);
+            let dc = first_term_dc.saturating_sub(gamma.saturating_mul(carn_total_count));
 
-            let mut dh_per_species: i32 = dh.checked_div(herb_species_count).unwrap_or(0);
-            let mut dc_per_species: i32 = dc.checked_div(carn_species_count).unwrap_or(0);
+            let mut dh_per_species = dh.checked_div(herb_species_count).unwrap_or(0);
+            let mut dc_per_species = dc.checked_div(carn_species_count).unwrap_or(0);
 
             // Divide by timestamp and clamp to -1 or 1
             let _dh_was_positive = dh_per_species >= 0;
             let _dc_was_positive = dc_per_species >= 0;
-            dh_per_species /= timestep;
-            dc_per_species /= timestep;
+            dh_per_species = dh_per_species.checked_div(timestep).unwrap_or(0);
+            dc_per_species = dc_per_species.checked_div(timestep).unwrap_or(0);
 
             // Build new demographics
             let mut new_total_count = 0;
@@ -389,10 +3420,13 @@ pub mod pallet {
 
             for (id, pop) in &region.population_demographics {
                 let data = SpeciesIdToSpeciesData::<T>::get(id);
-                let new_value = match data.diet {
-                    Diet::Herbivore => (*pop as i32 + dh_per_species).max(0) as u32,
-                    Diet::Carnivore => (*pop as i32 + dc_per_species).max(0) as u32,
+                let change = match data.diet {
+                    Diet::Herbivore => dh_per_species,
+                    Diet::Carnivore => dc_per_species,
                 };
+                let new_value = (*pop as i64)
+                    .saturating_add(change)
+                    .clamp(0, u32::MAX as i64) as u32;
                 new_demographics.insert(*id, new_value);
                 new_total_count += new_value as u64;
             }
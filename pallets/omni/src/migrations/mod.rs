@@ -0,0 +1,11 @@
+//! Storage migrations for the omni pallet
+//!
+//! Each storage version gets its own module with a stepped migration, which runtimes run through
+//! `pallet-migrations` so no single block has to migrate every account. Each migration only
+//! runs from the storage version it expects and sets the next one when it is done.
+//!
+//! `pallet-migrations` has no try-runtime hooks for stepped migrations, so each migration has
+//! `pre_upgrade` and `post_upgrade` checks to run around it instead.
+
+pub mod v1;
+pub mod v2;
@@ -0,0 +1,137 @@
+//! Version 1 adds [`JokeymonIdToOwner`], filled in here from every account's jokeymon
+
+use super::v2::v1::{AccountData, AccountToData};
+use crate::{Config, JokeymonIdToOwner, Pallet};
+use frame_support::{
+    migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+    pallet_prelude::*,
+    weights::WeightMeter,
+};
+
+#[cfg(feature = "try-runtime")]
+use crate::{types::RegionId, JokeymonIdToData, RegionIdToRegion};
+#[cfg(feature = "try-runtime")]
+use sp_runtime::{TryRuntimeError, Vec};
+
+/// Identifies the migration to `pallet-migrations`
+const MIGRATION_ID: [u8; 18] = *b"pallet-omni-mbm-v1";
+
+/// record `who` as the owner of each of its jokeymon
+fn backfill_owners<T: Config>(who: &T::AccountId, data: &AccountData<T>) {
+    for jokeymon_id in data.jokeymon.iter() {
+        JokeymonIdToOwner::<T>::insert(jokeymon_id, who);
+    }
+}
+
+/// Fill in the owner of every jokeymon, one account at a time across as many blocks as needed
+///
+/// The cursor is the last account migrated. The storage version is set to 1 after the last
+/// account.
+pub struct MigrateV0ToV1<T>(PhantomData<T>);
+
+impl<T: Config> MigrateV0ToV1<T> {
+    /// worst case weight of migrating one account
+    fn account_weight() -> Weight {
+        T::DbWeight::get().reads_writes(1, T::MaxJokeymonHoldable::get().into())
+    }
+
+    /// Totals to compare against once the migration is done
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+        Ok(Snapshot::<T>::take().encode())
+    }
+
+    /// Check the totals are unchanged and every held jokeymon has its owner
+    #[cfg(feature = "try-runtime")]
+    pub fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+        let before = Snapshot::<T>::decode(&mut &state[..])
+            .map_err(|_| "v1: failed to decode the pre upgrade snapshot")?;
+        ensure!(
+            before == Snapshot::<T>::take(),
+            "v1: jokeymon or region totals changed"
+        );
+        for (who, data) in AccountToData::<T>::iter() {
+            for jokeymon_id in data.jokeymon.iter() {
+                ensure!(
+                    JokeymonIdToOwner::<T>::get(jokeymon_id).as_ref() == Some(&who),
+                    "v1: held jokeymon is missing its owner"
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Config> SteppedMigration for MigrateV0ToV1<T> {
+    type Cursor = T::AccountId;
+    type Identifier = MigrationId<18>;
+
+    fn id() -> Self::Identifier {
+        MigrationId {
+            pallet_id: MIGRATION_ID,
+            version_from: 0,
+            version_to: 1,
+        }
+    }
+
+    fn step(
+        mut cursor: Option<Self::Cursor>,
+        meter: &mut WeightMeter,
+    ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+        if Pallet::<T>::on_chain_storage_version() != StorageVersion::new(0) {
+            return Ok(None);
+        }
+        let required = Self::account_weight();
+        if meter.remaining().any_lt(required) {
+            return Err(SteppedMigrationError::InsufficientWeight { required });
+        }
+
+        while meter.try_consume(required).is_ok() {
+            let mut accounts = match cursor {
+                Some(last) => {
+                    AccountToData::<T>::iter_from(AccountToData::<T>::hashed_key_for(last))
+                }
+                None => AccountToData::<T>::iter(),
+            };
+            match accounts.next() {
+                Some((who, data)) => {
+                    backfill_owners::<T>(&who, &data);
+                    cursor = Some(who);
+                }
+                None => {
+                    StorageVersion::new(1).put::<Pallet<T>>();
+                    return Ok(None);
+                }
+            }
+        }
+        Ok(cursor)
+    }
+}
+
+/// Totals that a migration must not change
+#[cfg(feature = "try-runtime")]
+#[derive(Encode, Decode, PartialEq, Eq)]
+struct Snapshot<T> {
+    jokeymon: u64,
+    held: u64,
+    regions: Vec<(RegionId, u64)>,
+    _marker: PhantomData<T>,
+}
+
+#[cfg(feature = "try-runtime")]
+impl<T: Config> Snapshot<T> {
+    fn take() -> Self {
+        let mut regions: Vec<_> = RegionIdToRegion::<T>::iter()
+            .map(|(region_id, region)| (region_id, region.total_population))
+            .collect();
+        regions.sort();
+        Self {
+            jokeymon: JokeymonIdToData::<T>::iter_keys().count() as u64,
+            held: AccountToData::<T>::iter_values()
+                .map(|data| data.jokeymon.len() as u64)
+                .sum(),
+            regions,
+            _marker: PhantomData,
+        }
+    }
+}
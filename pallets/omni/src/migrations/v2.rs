@@ -0,0 +1,136 @@
+//! Version 2 adds a party to [`AccountData`], filled with the first jokeymon each account holds
+
+use crate::{types::AccountData, AccountToData, Config, Pallet};
+use frame_support::{
+    migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+    pallet_prelude::*,
+    weights::WeightMeter,
+};
+
+#[cfg(feature = "try-runtime")]
+use sp_runtime::{TryRuntimeError, Vec};
+
+/// Identifies the migration to `pallet-migrations`
+const MIGRATION_ID: [u8; 18] = *b"pallet-omni-mbm-v2";
+
+/// Account storage as it was up to version 1, before accounts had a party
+pub mod v1 {
+    use crate::{
+        types::{JokeymonId, RegionId},
+        Config, Pallet,
+    };
+    use frame_support::{pallet_prelude::*, storage_alias, BoundedVec};
+
+    #[derive(Encode, Decode)]
+    pub struct AccountData<T: Config> {
+        pub current_region: RegionId,
+        pub jokeymon: BoundedVec<JokeymonId, T::MaxJokeymonHoldable>,
+    }
+
+    #[storage_alias]
+    pub type AccountToData<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        AccountData<T>,
+    >;
+}
+
+/// the version 2 account data, with the party made of the first jokeymon held
+fn migrate_account<T: Config>(old: v1::AccountData<T>) -> AccountData<T> {
+    let party = old
+        .jokeymon
+        .iter()
+        .copied()
+        .take(T::MaxTeamSize::get() as usize)
+        .collect::<sp_runtime::Vec<_>>();
+    AccountData {
+        current_region: old.current_region,
+        party: BoundedVec::truncate_from(party),
+        jokeymon: old.jokeymon,
+    }
+}
+
+/// Give every account a party, one account at a time across as many blocks as needed
+///
+/// The cursor is the last account migrated. The storage version is set to 2 after the last
+/// account.
+pub struct MigrateV1ToV2<T>(PhantomData<T>);
+
+impl<T: Config> MigrateV1ToV2<T> {
+    /// Every account's jokeymon, to compare against once the migration is done
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+        let held: Vec<_> = v1::AccountToData::<T>::iter()
+            .map(|(who, data)| (who, data.jokeymon.into_inner()))
+            .collect();
+        Ok(held.encode())
+    }
+
+    /// Check no account or jokeymon was lost and every party is the first jokeymon held
+    #[cfg(feature = "try-runtime")]
+    pub fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+        let held = Vec::<(T::AccountId, Vec<u64>)>::decode(&mut &state[..])
+            .map_err(|_| "v2: failed to decode the pre upgrade snapshot")?;
+        ensure!(
+            held.len() == AccountToData::<T>::iter_keys().count(),
+            "v2: number of accounts changed"
+        );
+        let max_party = T::MaxTeamSize::get() as usize;
+        for (who, jokeymon) in held {
+            let data = AccountToData::<T>::get(&who);
+            ensure!(
+                data.jokeymon[..] == jokeymon[..],
+                "v2: held jokeymon changed"
+            );
+            ensure!(
+                data.party[..] == jokeymon[..jokeymon.len().min(max_party)],
+                "v2: party is not the first jokeymon held"
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<T: Config> SteppedMigration for MigrateV1ToV2<T> {
+    type Cursor = T::AccountId;
+    type Identifier = MigrationId<18>;
+
+    fn id() -> Self::Identifier {
+        MigrationId {
+            pallet_id: MIGRATION_ID,
+            version_from: 1,
+            version_to: 2,
+        }
+    }
+
+    fn step(
+        mut cursor: Option<Self::Cursor>,
+        meter: &mut WeightMeter,
+    ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+        if Pallet::<T>::on_chain_storage_version() != StorageVersion::new(1) {
+            return Ok(None);
+        }
+        let required = T::DbWeight::get().reads_writes(1, 1);
+        if meter.remaining().any_lt(required) {
+            return Err(SteppedMigrationError::InsufficientWeight { required });
+        }
+
+        while meter.try_consume(required).is_ok() {
+            let mut accounts = match cursor {
+                Some(last) => {
+                    AccountToData::<T>::iter_keys_from(AccountToData::<T>::hashed_key_for(last))
+                }
+                None => AccountToData::<T>::iter_keys(),
+            };
+            let Some(who) = accounts.next() else {
+                StorageVersion::new(2).put::<Pallet<T>>();
+                return Ok(None);
+            };
+            let old = v1::AccountToData::<T>::get(&who).ok_or(SteppedMigrationError::Failed)?;
+            AccountToData::<T>::insert(&who, migrate_account(old));
+            cursor = Some(who);
+        }
+        Ok(cursor)
+    }
+}
@@ -1,8 +1,10 @@
 use crate::pallet as OmniPallet;
 use crate::types::*;
-use frame_support::{derive_impl, parameter_types, weights::constants::RocksDbWeight, BoundedBTreeMap};
+use frame_support::{
+    derive_impl, parameter_types, weights::constants::RocksDbWeight, BoundedBTreeMap, PalletId,
+};
 use frame_system::mocking::MockBlock;
-use sp_runtime::{traits::ConstU64, BuildStorage};
+use sp_runtime::{traits::ConstU64, BuildStorage, Permill};
 
 // Configure a mock runtime to test the pallet.
 #[frame_support::runtime]
@@ -27,6 +29,8 @@ mod test_runtime {
     pub type OmniModule = crate;
     #[runtime::pallet_index(2)]
     pub type RandomModule = pallet_insecure_randomness_collective_flip;
+    #[runtime::pallet_index(3)]
+    pub type Balances = pallet_balances;
 }
 
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
@@ -35,29 +39,103 @@ impl frame_system::Config for Test {
     type Block = MockBlock<Test>;
     type BlockHashCount = ConstU64<250>;
     type DbWeight = RocksDbWeight;
+    type AccountData = pallet_balances::AccountData<u64>;
 }
 
 impl pallet_insecure_randomness_collective_flip::Config for Test {}
 
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+    type AccountStore = System;
+}
+
 parameter_types! {
     pub const MaxJokeymonInRegion : u32 = 50;
     pub const MaxJokeymonHoldable : u32 = 100;
+    pub const OmniPalletId: PalletId = PalletId(*b"jk/omni_");
+    pub const CatchFee: u64 = 10;
+    pub const TravelFee: u64 = 20;
+    pub const JokeymonDeposit: u64 = 5;
+    pub const MarketplaceFee: Permill = Permill::from_percent(5);
+    pub const TreasuryAccount: u64 = 999;
+    pub const MaxActiveAuctions: u32 = 10;
+    pub const MaxAuctionDuration: u64 = 100;
+    pub const MaxTeamSize: u32 = 6;
+    pub const MaxBattleRounds: u32 = 100;
+    pub const SeasonLength: u64 = 100;
+    pub const LeaderboardSize: u32 = 3;
+    pub const SeasonReward: u64 = 300;
+    pub const MaxSpecies: u32 = 16;
+    pub const PokedexMilestone: u32 = 2;
+    pub const PokedexReward: u64 = 50;
+    pub const MaxQuests: u32 = 4;
+    pub const GymReward: u64 = 3;
+    pub const MaxGuildMembers: u32 = 3;
+    pub const PopulationSampleInterval: u64 = 5;
+    pub const PopulationHistoryLength: u32 = 3;
+    pub const MaxRegionsSampledPerBlock: u32 = 1;
+    pub const ProtectionThreshold: Permill = Permill::from_percent(5);
+    pub static MaxCatchStamina: u32 = 1_000;
+    pub static CatchStaminaRegen: u64 = 10;
+    pub const RatedMatchCooldown: u64 = 10;
+    pub const BattleExperience: u32 = 60;
 }
 
+/// Accounts endowed at genesis
+pub const ENDOWED_ACCOUNTS: u64 = 500;
+/// Balance of each endowed account
+pub const INITIAL_BALANCE: u64 = 10_000;
+
 impl crate::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
     type RandomSource = RandomModule;
     type MaxSpeciesInRegion = MaxJokeymonInRegion;
     type MaxJokeymonHoldable = MaxJokeymonHoldable;
+    type Currency = Balances;
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type PalletId = OmniPalletId;
+    type CatchFee = CatchFee;
+    type TravelFee = TravelFee;
+    type JokeymonDeposit = JokeymonDeposit;
+    type MarketplaceFee = MarketplaceFee;
+    type TreasuryAccount = TreasuryAccount;
+    type MaxActiveAuctions = MaxActiveAuctions;
+    type MaxAuctionDuration = MaxAuctionDuration;
+    type MaxTeamSize = MaxTeamSize;
+    type MaxBattleRounds = MaxBattleRounds;
+    type SeasonLength = SeasonLength;
+    type LeaderboardSize = LeaderboardSize;
+    type SeasonReward = SeasonReward;
+    type MaxSpecies = MaxSpecies;
+    type PokedexMilestone = PokedexMilestone;
+    type PokedexReward = PokedexReward;
+    type AdminOrigin = frame_system::EnsureRoot<u64>;
+    type MaxQuests = MaxQuests;
+    type GymReward = GymReward;
+    type MaxGuildMembers = MaxGuildMembers;
+    type PopulationSampleInterval = PopulationSampleInterval;
+    type PopulationHistoryLength = PopulationHistoryLength;
+    type MaxRegionsSampledPerBlock = MaxRegionsSampledPerBlock;
+    type ProtectionThreshold = ProtectionThreshold;
+    type MaxCatchStamina = MaxCatchStamina;
+    type CatchStaminaRegen = CatchStaminaRegen;
+    type RatedMatchCooldown = RatedMatchCooldown;
+    type BattleExperience = BattleExperience;
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    let t = RuntimeGenesisConfig::default()
-        .build_storage()
-        .unwrap()
-        .into();
+    let t = RuntimeGenesisConfig {
+        balances: pallet_balances::GenesisConfig {
+            balances: (0..ENDOWED_ACCOUNTS)
+                .map(|who| (who, INITIAL_BALANCE))
+                .collect(),
+        },
+        ..Default::default()
+    }
+    .build_storage()
+    .unwrap();
     let mut ext = sp_io::TestExternalities::new(t);
     ext.execute_with(|| {
         System::set_block_number(1);
@@ -71,7 +149,8 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 
 /// Mock a test jokeymon region
 pub(super) fn get_test_region<T: crate::Config>() -> Region<T> {
-    let mut map: BoundedBTreeMap<JokeymonSpeciesId, JokeymonCount, T::MaxSpeciesInRegion> = BoundedBTreeMap::new();
+    let mut map: BoundedBTreeMap<JokeymonSpeciesId, JokeymonCount, T::MaxSpeciesInRegion> =
+        BoundedBTreeMap::new();
     map.try_insert(0u32, 150).unwrap();
     map.try_insert(1u32, 150).unwrap();
     map.try_insert(2u32, 150).unwrap();
@@ -102,11 +181,11 @@ pub(super) fn set_species_data(
     OmniPallet::SpeciesIdToSpeciesData::<Test>::set(
         id,
         JokeymonSpeciesData {
-            id: id,
-            avg_weight: avg_weight,
-            avg_daily_food_consumption: avg_daily_food_consumption,
-            diet: diet,
-            evolves_to: evolves_to,
+            id,
+            avg_weight,
+            avg_daily_food_consumption,
+            diet,
+            evolves_to,
         },
     );
 }
@@ -117,3 +196,21 @@ pub(super) fn setup_test_species_data() {
     set_species_data(1, 20, 25, Diet::Herbivore, Some(2));
     set_species_data(2, 30, 45, Diet::Carnivore, None);
 }
+
+/// Give an account a jokeymon without catching it, it joins the party if there is room
+pub(super) fn give_jokeymon(who: u64, species_id: JokeymonSpeciesId) -> JokeymonId {
+    let jokeymon_id = OmniModule::get_and_increment_jokeymon_id_nonce();
+    OmniPallet::JokeymonIdToData::<Test>::insert(
+        jokeymon_id,
+        JokeymonData::<Test> {
+            id: species_id,
+            birth_date: System::block_number(),
+        },
+    );
+    OmniPallet::JokeymonIdToOwner::<Test>::insert(jokeymon_id, who);
+    OmniPallet::AccountToData::<Test>::mutate(who, |data| {
+        data.jokeymon.try_push(jokeymon_id).unwrap();
+        let _ = data.party.try_push(jokeymon_id);
+    });
+    jokeymon_id
+}
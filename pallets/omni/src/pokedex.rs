@@ -0,0 +1,88 @@
+//! Pokedex style discovery tracking
+//!
+//! Every account keeps two bitsets over species ids, the species it has seen and the species
+//! it has caught. Species ids at or above `MaxSpecies` can't be tracked and are ignored.
+
+use crate::types::{JokeymonSpeciesId, Pokedex, SpeciesBitset};
+use frame_support::traits::Get;
+use sp_runtime::Permill;
+
+/// Whether `species_id` is set in `bitset`
+pub fn contains<T: crate::Config>(
+    bitset: &SpeciesBitset<T>,
+    species_id: JokeymonSpeciesId,
+) -> bool {
+    let (byte, mask) = position(species_id);
+    bitset.get(byte).is_some_and(|b| b & mask != 0)
+}
+
+/// Set `species_id` in `bitset`, returns whether it wasn't set before
+pub fn insert<T: crate::Config>(
+    bitset: &mut SpeciesBitset<T>,
+    species_id: JokeymonSpeciesId,
+) -> bool {
+    if species_id >= <T as crate::Config>::MaxSpecies::get() || contains(bitset, species_id) {
+        return false;
+    }
+    let (byte, mask) = position(species_id);
+    while bitset.len() <= byte {
+        if bitset.try_push(0).is_err() {
+            return false;
+        }
+    }
+    bitset[byte] |= mask;
+    true
+}
+
+/// Number of species set in `bitset`
+pub fn count<T: crate::Config>(bitset: &SpeciesBitset<T>) -> u32 {
+    bitset.iter().map(|b| b.count_ones()).sum()
+}
+
+fn position(species_id: JokeymonSpeciesId) -> (usize, u8) {
+    ((species_id / 8) as usize, 1 << (species_id % 8))
+}
+
+impl<T: crate::Config> Pokedex<T> {
+    /// Record a species as seen, returns whether it is new
+    pub fn see(&mut self, species_id: JokeymonSpeciesId) -> bool {
+        insert::<T>(&mut self.seen, species_id)
+    }
+
+    /// Record a species as caught, and so also seen, returns whether it is newly caught
+    pub fn catch(&mut self, species_id: JokeymonSpeciesId) -> bool {
+        self.see(species_id);
+        insert::<T>(&mut self.caught, species_id)
+    }
+
+    pub fn has_seen(&self, species_id: JokeymonSpeciesId) -> bool {
+        contains::<T>(&self.seen, species_id)
+    }
+
+    pub fn has_caught(&self, species_id: JokeymonSpeciesId) -> bool {
+        contains::<T>(&self.caught, species_id)
+    }
+
+    pub fn seen_count(&self) -> u32 {
+        count::<T>(&self.seen)
+    }
+
+    pub fn caught_count(&self) -> u32 {
+        count::<T>(&self.caught)
+    }
+
+    /// Share of `species` that has been caught, nothing to catch counts as complete
+    pub fn completion<'a>(&self, species: impl Iterator<Item = &'a JokeymonSpeciesId>) -> Permill {
+        let (mut total, mut caught) = (0u32, 0u32);
+        for species_id in species {
+            total += 1;
+            if self.has_caught(*species_id) {
+                caught += 1;
+            }
+        }
+        if total == 0 {
+            return Permill::one();
+        }
+        Permill::from_rational(caught, total)
+    }
+}
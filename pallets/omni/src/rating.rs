@@ -0,0 +1,60 @@
+//! Elo ratings for the rated PvP ladder
+//!
+//! Runtime code can't use floats, so the expected score is read from a table of the logistic
+//! curve `1 / (1 + 10^(d / 400))` sampled every [`STEP`] rating points.
+
+use crate::types::Rating;
+use sp_runtime::Permill;
+
+/// The rating every account starts a season with
+pub const INITIAL_RATING: Rating = 1_000;
+
+/// How far a single match can move a rating
+pub const K_FACTOR: i64 = 32;
+
+/// Rating difference between table entries
+const STEP: u32 = 25;
+
+/// Expected score, in parts per million, of the weaker player for a rating difference of
+/// `index * STEP`. Differences beyond the table are treated as the last entry.
+const EXPECTED_SCORE: [u32; 33] = [
+    500000, 464084, 428537, 393712, 359935, 327490, 296615, 267493, 240253, 214973, 191682, 170367,
+    150980, 133443, 117662, 103523, 90909, 79695, 69758, 60978, 53240, 46435, 40463, 35231, 30653,
+    26654, 23164, 20122, 17472, 15166, 13160, 11416, 9901,
+];
+
+/// The result of a match from one player's point of view
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Score {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl Score {
+    fn parts(self) -> i64 {
+        match self {
+            Score::Win => 1_000_000,
+            Score::Draw => 500_000,
+            Score::Loss => 0,
+        }
+    }
+}
+
+/// The chance `rating` beats `opponent`
+pub fn expected_score(rating: Rating, opponent: Rating) -> Permill {
+    let index = (rating.abs_diff(opponent) / STEP) as usize;
+    let weaker = EXPECTED_SCORE[index.min(EXPECTED_SCORE.len() - 1)];
+    if rating <= opponent {
+        Permill::from_parts(weaker)
+    } else {
+        Permill::from_parts(1_000_000 - weaker)
+    }
+}
+
+/// The new rating of a player after a match against `opponent`
+pub fn updated_rating(rating: Rating, opponent: Rating, score: Score) -> Rating {
+    let expected = expected_score(rating, opponent).deconstruct() as i64;
+    let change = (K_FACTOR * (score.parts() - expected)) / 1_000_000;
+    (rating as i64 + change).clamp(0, Rating::MAX as i64) as Rating
+}
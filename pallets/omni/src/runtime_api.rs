@@ -0,0 +1,22 @@
+//! Runtime API definition for the omni pallet
+
+use crate::types::{JokeymonCount, JokeymonSpeciesId, Rating, RegionId};
+use codec::Codec;
+use scale_info::prelude::collections::BTreeMap;
+use sp_runtime::{Permill, Vec};
+
+sp_api::decl_runtime_apis! {
+    /// Read only queries into the jokeymon world
+    #[api_version(2)]
+    pub trait OmniApi<AccountId, BlockNumber> where AccountId: Codec, BlockNumber: Codec {
+        /// A page of the current season leaderboard, highest rated first
+        fn leaderboard(page: u32, page_size: u32) -> Vec<(AccountId, Rating)>;
+        /// Share of the species living in each region that an account has caught
+        fn pokedex_completion(who: AccountId) -> Vec<(RegionId, Permill)>;
+        /// The sampled populations of a region, oldest first
+        #[api_version(2)]
+        fn population_history(
+            region_id: RegionId,
+        ) -> Vec<(BlockNumber, BTreeMap<JokeymonSpeciesId, JokeymonCount>)>;
+    }
+}
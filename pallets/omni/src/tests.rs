@@ -1,6 +1,31 @@
-use crate::{mock::*, pallet as OmniPallet, types::*, Error};
-use frame_support::assert_noop;
-use sp_runtime::Permill;
+use crate::{
+    battle::{self, BattleOutcome, Fighter},
+    extension::{CheckCatchStamina, OUT_OF_STAMINA},
+    impl_nonfungibles::{DIET_KEY, SPECIES_KEY},
+    migrations::{v1, v2},
+    mock::*,
+    pallet as OmniPallet, pokedex,
+    rating::{self, Score, INITIAL_RATING},
+    types::*,
+    worldgen::{self, WorldParams},
+    Error, Event, HoldReason,
+};
+use codec::{Encode, MaxEncodedLen};
+use frame_support::{
+    assert_noop, assert_ok,
+    dispatch::DispatchInfo,
+    migrations::SteppedMigration,
+    traits::{
+        fungible::{InspectHold, Mutate},
+        tokens::nonfungibles_v2::{Inspect, Transfer},
+        GetStorageVersion, Hooks, StorageVersion,
+    },
+    weights::{Weight, WeightMeter},
+};
+use sp_runtime::{
+    traits::SignedExtension, transaction_validity::InvalidTransaction, BuildStorage, Permill,
+    TokenError,
+};
 
 // ---- Population Dynamics ----
 
@@ -9,7 +34,7 @@ fn population_remains_stable_mixed_diets() {
     new_test_ext().execute_with(|| {
         let mut region = get_test_region::<Test>();
         for _ in 0..10_000 {
-            OmniModule::update_regional_population(&mut region);
+            OmniModule::update_regional_population(&mut region, &PopulationModel::default());
         }
         assert!(region.total_population > 0);
         assert!(region.total_population < 100_000);
@@ -22,7 +47,7 @@ fn population_remains_stable_all_herbivores() {
         let mut region = get_test_region::<Test>();
         set_species_data(2, 30, 45, Diet::Herbivore, None);
         for _ in 0..10_000 {
-            OmniModule::update_regional_population(&mut region);
+            OmniModule::update_regional_population(&mut region, &PopulationModel::default());
         }
         assert!(region.total_population > 0);
         assert!(region.total_population < 100_000);
@@ -36,7 +61,7 @@ fn population_remains_stable_all_carnivores() {
         set_species_data(0, 10, 10, Diet::Carnivore, Some(1));
         set_species_data(1, 20, 25, Diet::Carnivore, Some(2));
         for _ in 0..10_000 {
-            OmniModule::update_regional_population(&mut region);
+            OmniModule::update_regional_population(&mut region, &PopulationModel::default());
         }
         assert!(region.total_population > 0);
         assert!(region.total_population < 10_000);
@@ -48,9 +73,236 @@ fn total_population_updates_with_update() {
     new_test_ext().execute_with(|| {
         let mut region = get_test_region::<Test>();
         for _ in 0..10_000 {
-            OmniModule::update_regional_population(&mut region);
+            OmniModule::update_regional_population(&mut region, &PopulationModel::default());
         }
-        assert_eq!(region.total_population, region.population_demographics.values().sum::<JokeymonCount>() as u64);
+        assert_eq!(
+            region.total_population,
+            region
+                .population_demographics
+                .values()
+                .sum::<JokeymonCount>() as u64
+        );
+    });
+}
+
+#[test]
+fn population_update_survives_extreme_regions() {
+    new_test_ext().execute_with(|| {
+        // states the admin calls reject, but that must still never halt the chain
+        set_species_data(0, 10, 0, Diet::Herbivore, Some(1));
+        set_species_data(1, 20, 0, Diet::Herbivore, Some(2));
+        set_species_data(2, 30, 45, Diet::Carnivore, None);
+        let mut region = get_test_region::<Test>();
+        region.population_demographics = demographics(&[
+            (0, JokeymonCount::MAX),
+            (1, JokeymonCount::MAX),
+            (2, JokeymonCount::MAX),
+        ]);
+        for _ in 0..10 {
+            OmniModule::update_regional_population(&mut region, &PopulationModel::default());
+        }
+        assert_eq!(
+            region.total_population,
+            region
+                .population_demographics
+                .values()
+                .map(|count| *count as u64)
+                .sum::<u64>()
+        );
+    });
+}
+
+#[test]
+fn update_populations_follows_the_model() {
+    new_test_ext().execute_with(|| {
+        let world = worldgen::generate::<Test>(&WorldParams {
+            seed: 0,
+            width: 1,
+            height: 1,
+            herbivores: 1,
+            carnivores: 1,
+        });
+        let region = world.regions[0].clone();
+        for species in &world.species {
+            OmniPallet::SpeciesIdToSpeciesData::<Test>::insert(species.id, species);
+        }
+        OmniPallet::RegionIdToRegion::<Test>::insert(region.id, &region);
+
+        OmniModule::update_populations(&PopulationModel::default());
+        assert_eq!(OmniPallet::RegionIdToRegion::<Test>::get(region.id), region);
+
+        // carnivores die slower than the default, so they outgrow the equilibrium
+        let model = PopulationModel {
+            timestep: 1,
+            gamma: 100,
+            ..Default::default()
+        };
+        OmniModule::update_populations(&model);
+        let carnivores = |region: &Region<Test>| region.population_demographics[&1];
+        assert!(
+            carnivores(&OmniPallet::RegionIdToRegion::<Test>::get(region.id)) > carnivores(&region)
+        );
+    });
+}
+
+#[test]
+fn population_updates_emit_changed_regions() {
+    new_test_ext().execute_with(|| {
+        let before = OmniPallet::RegionIdToRegion::<Test>::get(0);
+        OmniModule::update_populations(&PopulationModel::default());
+        let after = OmniPallet::RegionIdToRegion::<Test>::get(0);
+
+        let changes: Vec<_> = after
+            .population_demographics
+            .iter()
+            .map(|(id, count)| {
+                (
+                    *id,
+                    *count as i64 - before.population_demographics[id] as i64,
+                )
+            })
+            .filter(|(_, change)| *change != 0)
+            .collect();
+        assert!(!changes.is_empty());
+        System::assert_last_event(
+            Event::RegionPopulationUpdated {
+                region_id: 0,
+                changes: changes.try_into().unwrap(),
+            }
+            .into(),
+        );
+
+        // a region at equilibrium doesn't change, so nothing is emitted for it
+        let world = worldgen::generate::<Test>(&WorldParams {
+            seed: 0,
+            width: 1,
+            height: 1,
+            herbivores: 1,
+            carnivores: 1,
+        });
+        for species in &world.species {
+            OmniPallet::SpeciesIdToSpeciesData::<Test>::insert(species.id, species);
+        }
+        OmniPallet::RegionIdToRegion::<Test>::remove(0);
+        OmniPallet::RegionIdToRegion::<Test>::insert(world.regions[0].id, &world.regions[0]);
+        System::reset_events();
+        let weight = OmniModule::update_populations(&PopulationModel::default());
+        assert!(System::events().is_empty());
+        // so only the region and its species' data are read
+        assert_eq!(
+            weight,
+            Weight::from_parts(10_000, 0)
+                + <Test as frame_system::Config>::DbWeight::get().reads(1 + 2)
+        );
+        // and every species sits at its carrying capacity
+        let region = &world.regions[0];
+        assert_eq!(
+            OmniModule::carrying_capacities(region),
+            region
+                .population_demographics
+                .iter()
+                .map(|(id, count)| (*id, *count as u64))
+                .collect::<Vec<_>>()
+        );
+    });
+}
+
+#[test]
+fn population_model_equilibrium() {
+    let model = PopulationModel::default();
+    // carnivores hold herbivores at gamma / delta, and are held at alpha / beta * (1 - 200 / K)
+    assert_eq!(model.equilibrium(1_000), (200, 160));
+    assert_eq!(model.equilibrium(400), (200, 100));
+    // carnivores starve when the region can't feed more herbivores than that
+    assert_eq!(model.equilibrium(200), (200, 0));
+    assert_eq!(model.equilibrium(50), (50, 0));
+    // without predation herbivores fill the region
+    let no_predation = PopulationModel { beta: 0, ..model };
+    assert_eq!(no_predation.equilibrium(1_000), (1_000, 0));
+}
+
+#[test]
+fn population_update_weight_grows_with_the_world() {
+    new_test_ext().execute_with(|| {
+        let db_weight = <Test as frame_system::Config>::DbWeight::get();
+        // the mock region has three species
+        let one_region = OmniModule::update_populations(&PopulationModel::default());
+        assert_eq!(
+            one_region,
+            Weight::from_parts(10_000, 0).saturating_add(db_weight.reads_writes(11, 8))
+        );
+
+        let region = get_test_region::<Test>();
+        OmniPallet::RegionIdToRegion::<Test>::insert(1, Region { id: 1, ..region });
+        assert_eq!(
+            OmniModule::update_populations(&PopulationModel::default()),
+            one_region * 2
+        );
+        // a block without samples or a season end only updates populations, and checks for a
+        // sample still being taken
+        assert_eq!(
+            OmniModule::on_initialize(1),
+            one_region * 2 + db_weight.reads(1) + Weight::from_parts(0, 4)
+        );
+    });
+}
+
+#[test]
+fn population_history_keeps_the_last_samples() {
+    new_test_ext().execute_with(|| {
+        let mut expected = Vec::new();
+        for n in 1..=20u64 {
+            System::set_block_number(n);
+            OmniModule::on_initialize(n);
+            if n % PopulationSampleInterval::get() == 0 {
+                let region = OmniPallet::RegionIdToRegion::<Test>::get(0);
+                expected.push((n, region.population_demographics.into_inner()));
+            }
+        }
+
+        // only the newest samples are kept, oldest first
+        let history = OmniModule::population_history(0);
+        assert_eq!(history.len(), PopulationHistoryLength::get() as usize);
+        assert_eq!(history, expected[1..]);
+        assert_eq!(
+            history.iter().map(|(block, _)| *block).collect::<Vec<_>>(),
+            vec![10, 15, 20]
+        );
+        assert!(OmniModule::population_history(1).is_empty());
+    });
+}
+
+#[test]
+fn population_samples_are_spread_across_blocks() {
+    new_test_ext().execute_with(|| {
+        let region = get_test_region::<Test>();
+        OmniPallet::RegionIdToRegion::<Test>::insert(1, Region { id: 1, ..region });
+        let interval = PopulationSampleInterval::get();
+        let db_weight = <Test as frame_system::Config>::DbWeight::get();
+        let region_proof = (Region::<Test>::max_encoded_len() + 4) as u64;
+
+        // one region a block, the sample carries on where it stopped
+        assert_eq!(
+            OmniModule::sample_populations_if_due(interval),
+            db_weight.reads_writes(3, 3) + Weight::from_parts(0, 4 + region_proof)
+        );
+        let first = OmniPallet::PopulationSampleCursor::<Test>::get().unwrap();
+        assert_eq!(OmniModule::population_history(first).len(), 1);
+        OmniModule::sample_populations_if_due(interval + 1);
+        assert_eq!(OmniModule::population_history(1 - first).len(), 1);
+
+        // the last block finds nothing left and closes the sample
+        assert_eq!(
+            OmniModule::sample_populations_if_due(interval + 2),
+            db_weight.reads_writes(1, 1) + Weight::from_parts(0, 4)
+        );
+        assert_eq!(OmniPallet::PopulationSampleCursor::<Test>::get(), None);
+        assert_eq!(
+            OmniModule::sample_populations_if_due(interval + 3),
+            db_weight.reads(1) + Weight::from_parts(0, 4)
+        );
+        assert_eq!(OmniModule::population_history(first).len(), 1);
+        assert_eq!(OmniModule::population_history(1 - first).len(), 1);
     });
 }
 
@@ -75,11 +327,11 @@ fn catch_works_at_bounds() {
     new_test_ext().execute_with(|| {
         let region = get_test_region();
         // lower bound
-        let mut id = OmniPallet::Pallet::<Test>::get_jokeymon_in_region(&region, Permill::zero());
-        assert_ne!(id, u32::MAX);
+        let id = OmniPallet::Pallet::<Test>::get_jokeymon_in_region(&region, 0);
+        assert_eq!(id, Ok(0));
         // upper bound
-        id = OmniPallet::Pallet::<Test>::get_jokeymon_in_region(&region, Permill::one());
-        assert_ne!(id, u32::MAX);
+        let id = OmniPallet::Pallet::<Test>::get_jokeymon_in_region(&region, u64::MAX);
+        assert!(id.is_ok());
     })
 }
 
@@ -130,7 +382,7 @@ fn catching_too_many_fails() {
 #[test]
 fn depleting_region_works() {
     new_test_ext().execute_with(|| {
-        let region = OmniPallet::RegionIdToRegion::<Test>::get(&0);
+        let region = OmniPallet::RegionIdToRegion::<Test>::get(0);
         let num_jokeymon = region.total_population;
 
         // catch the entire population
@@ -197,3 +449,3152 @@ fn increment_species_should_work() {
         );
     });
 }
+
+// ---- Failed Catches ----
+
+/// Catching fails with `error` and leaves the region and nonces as they were
+fn assert_catch_fails_cleanly(who: u64, error: sp_runtime::DispatchError) {
+    let region = OmniPallet::RegionIdToRegion::<Test>::get(0);
+    let jokeymon_nonce = OmniPallet::JokeymonIdNonce::<Test>::get();
+    let random_nonce = OmniPallet::RandomNonce::<Test>::get();
+    let held = OmniPallet::AccountToData::<Test>::get(who).jokeymon;
+
+    assert_noop!(
+        OmniModule::catch_jokeymon(RuntimeOrigin::signed(who)),
+        error
+    );
+
+    assert_eq!(OmniPallet::RegionIdToRegion::<Test>::get(0), region);
+    assert_eq!(OmniPallet::JokeymonIdNonce::<Test>::get(), jokeymon_nonce);
+    assert_eq!(OmniPallet::RandomNonce::<Test>::get(), random_nonce);
+    assert_eq!(OmniPallet::AccountToData::<Test>::get(who).jokeymon, held);
+}
+
+#[test]
+fn full_account_catch_changes_nothing() {
+    new_test_ext().execute_with(|| {
+        for _ in 0..MaxJokeymonHoldable::get() {
+            give_jokeymon(0, 0);
+        }
+        assert_catch_fails_cleanly(0, Error::<Test>::TooManyJokeymon.into());
+    });
+}
+
+#[test]
+fn empty_region_catch_changes_nothing() {
+    new_test_ext().execute_with(|| {
+        OmniPallet::RegionIdToRegion::<Test>::mutate(0, |region| {
+            for species_id in 0..3 {
+                OmniModule::decrement_species_in_population(region, species_id, 150);
+            }
+        });
+        assert_catch_fails_cleanly(0, Error::<Test>::NoCatchableJokeymon.into());
+    });
+}
+
+#[test]
+fn unaffordable_catch_changes_nothing() {
+    new_test_ext().execute_with(|| {
+        assert_catch_fails_cleanly(ENDOWED_ACCOUNTS, TokenError::FundsUnavailable.into());
+
+        // enough for the fee but not the deposit
+        assert_ok!(Balances::mint_into(&ENDOWED_ACCOUNTS, CatchFee::get() + 1));
+        assert_catch_fails_cleanly(ENDOWED_ACCOUNTS, TokenError::FundsUnavailable.into());
+    });
+}
+
+// ---- Catch Stamina ----
+
+#[test]
+fn catch_stamina_limits_catches_and_regenerates() {
+    new_test_ext().execute_with(|| {
+        MaxCatchStamina::set(2);
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0)));
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0)));
+        assert_eq!(OmniModule::catch_stamina(&0).points, 0);
+        assert_catch_fails_cleanly(0, Error::<Test>::OutOfStamina.into());
+
+        // other accounts have their own stamina
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(1)));
+
+        // one point comes back every `CatchStaminaRegen` blocks
+        System::set_block_number(1 + CatchStaminaRegen::get() - 1);
+        assert_catch_fails_cleanly(0, Error::<Test>::OutOfStamina.into());
+        System::set_block_number(1 + CatchStaminaRegen::get());
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0)));
+        assert_catch_fails_cleanly(0, Error::<Test>::OutOfStamina.into());
+
+        // and never more than the maximum
+        System::set_block_number(1 + 10 * CatchStaminaRegen::get());
+        assert_eq!(
+            OmniModule::catch_stamina(&0),
+            CatchStamina {
+                points: 2,
+                updated: System::block_number()
+            }
+        );
+    });
+}
+
+#[test]
+fn catches_are_not_tracked_without_stamina_regen() {
+    new_test_ext().execute_with(|| {
+        MaxCatchStamina::set(1);
+        CatchStaminaRegen::set(0);
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0)));
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0)));
+        assert!(OmniPallet::Stamina::<Test>::get(0u64).is_none());
+    });
+}
+
+#[test]
+fn single_account_cannot_deplete_region() {
+    new_test_ext().execute_with(|| {
+        MaxCatchStamina::set(5);
+        let total = OmniPallet::RegionIdToRegion::<Test>::get(0).total_population;
+
+        for _ in 0..total {
+            let _ = OmniModule::catch_jokeymon(RuntimeOrigin::signed(0));
+        }
+
+        assert_eq!(
+            OmniPallet::RegionIdToRegion::<Test>::get(0).total_population,
+            total - 5
+        );
+        assert_eq!(OmniPallet::AccountToData::<Test>::get(0).jokeymon.len(), 5);
+    });
+}
+
+#[test]
+fn stamina_extension_rejects_catches_without_stamina() {
+    new_test_ext().execute_with(|| {
+        MaxCatchStamina::set(1);
+        let catch = RuntimeCall::OmniModule(crate::Call::catch_jokeymon {});
+        let remark = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+        let info = DispatchInfo::default();
+        let extension = CheckCatchStamina::<Test>::new();
+
+        // catches of an account with the same stamina provide the same tag
+        let valid = extension.validate(&0, &catch, &info, 0).unwrap();
+        assert_eq!(
+            valid.provides,
+            vec![("CheckCatchStamina", (0u64, 1u32)).encode()]
+        );
+        assert!(valid.requires.is_empty());
+        assert_eq!(valid.longevity, CatchStaminaRegen::get());
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0)));
+
+        // stamina comes back, so the catch will be valid later
+        assert_eq!(
+            extension.validate(&0, &catch, &info, 0),
+            Err(InvalidTransaction::Future.into())
+        );
+        assert_eq!(
+            extension.clone().pre_dispatch(&0, &catch, &info, 0),
+            Err(InvalidTransaction::Future.into())
+        );
+
+        // other calls and other accounts pass
+        assert_ok!(extension.validate(&0, &remark, &info, 0));
+        assert_ok!(extension.validate(&1, &catch, &info, 0));
+
+        System::set_block_number(1 + CatchStaminaRegen::get());
+        assert_ok!(extension.validate(&0, &catch, &info, 0));
+    });
+}
+
+#[test]
+fn stamina_extension_rejects_catches_without_regeneration() {
+    new_test_ext().execute_with(|| {
+        MaxCatchStamina::set(0);
+        CatchStaminaRegen::set(0);
+        let catch = RuntimeCall::OmniModule(crate::Call::catch_jokeymon {});
+        let extension = CheckCatchStamina::<Test>::new();
+
+        assert_eq!(
+            extension.validate(&0, &catch, &DispatchInfo::default(), 0),
+            Err(InvalidTransaction::Custom(OUT_OF_STAMINA).into())
+        );
+    });
+}
+
+// ---- Catch Distribution ----
+
+/// Count the species picked by `rolls` weighted by the given demographics
+fn sample(
+    demographics: &[(JokeymonSpeciesId, JokeymonCount)],
+    rolls: impl Iterator<Item = u64>,
+) -> Vec<(JokeymonSpeciesId, u64)> {
+    let mut region = get_test_region::<Test>();
+    region.population_demographics = Default::default();
+    region.total_population = 0;
+    for (id, count) in demographics {
+        OmniModule::increment_species_in_population(&mut region, *id, *count).unwrap();
+    }
+
+    let weights: Vec<_> = region
+        .population_demographics
+        .iter()
+        .map(|(id, count)| (*id, *count as u64))
+        .collect();
+    let mut counts: Vec<(JokeymonSpeciesId, u64)> =
+        demographics.iter().map(|(id, _)| (*id, 0)).collect();
+    for roll in rolls {
+        let id = OmniModule::pick_weighted(&weights, roll).unwrap();
+        counts
+            .iter_mut()
+            .find(|(species_id, _)| *species_id == id)
+            .unwrap()
+            .1 += 1;
+    }
+    counts
+}
+
+/// Uniformly spread rolls
+fn hashed_rolls(n: u64) -> impl Iterator<Item = u64> {
+    (0..n).map(|i| {
+        u64::from_le_bytes(
+            sp_io::hashing::blake2_128(&i.encode())[..8]
+                .try_into()
+                .unwrap(),
+        )
+    })
+}
+
+#[test]
+fn sampler_is_exactly_proportional() {
+    new_test_ext().execute_with(|| {
+        // every roll below the total population hits each individual exactly once
+        let demographics = [(0, 1), (3, 10), (7, 1_000), (9, 89)];
+        assert_eq!(
+            sample(&demographics, 0..1_100),
+            vec![(0, 1), (3, 10), (7, 1_000), (9, 89)]
+        );
+        // and wraps around beyond it
+        assert_eq!(
+            sample(&demographics, 0..3_300),
+            vec![(0, 3), (3, 30), (7, 3_000), (9, 267)]
+        );
+    });
+}
+
+#[test]
+fn sampler_matches_population_over_random_rolls() {
+    new_test_ext().execute_with(|| {
+        let demographics = [(0, 100), (1, 300), (2, 600)];
+        let rolls = 60_000;
+        for (id, hits) in sample(&demographics, hashed_rolls(rolls)) {
+            let count = demographics
+                .iter()
+                .find(|(species_id, _)| *species_id == id)
+                .unwrap()
+                .1 as u64;
+            let expected = rolls * count / 1_000;
+            // about four standard deviations for the rarest species
+            assert!(
+                hits.abs_diff(expected) < expected / 20,
+                "species {id}: {hits} hits, expected {expected}"
+            );
+        }
+    });
+}
+
+#[test]
+fn sampler_never_picks_extinct_species() {
+    new_test_ext().execute_with(|| {
+        let mut region = get_test_region::<Test>();
+        OmniModule::decrement_species_in_population(&mut region, 1, 150);
+        for roll in hashed_rolls(1_000) {
+            assert_ne!(OmniModule::get_jokeymon_in_region(&region, roll), Ok(1));
+        }
+
+        OmniModule::decrement_species_in_population(&mut region, 0, 150);
+        OmniModule::decrement_species_in_population(&mut region, 2, 150);
+        assert_eq!(
+            OmniModule::get_jokeymon_in_region(&region, 0),
+            Err(Error::<Test>::NoCatchableJokeymon)
+        );
+    });
+}
+
+// ---- Population Pressure ----
+
+/// Set a species' population in the test region and refresh its protection
+fn set_population(species_id: JokeymonSpeciesId, count: JokeymonCount) {
+    OmniPallet::RegionIdToRegion::<Test>::mutate(0, |region| {
+        let previous = region.population_demographics[&species_id];
+        region
+            .population_demographics
+            .try_insert(species_id, count)
+            .unwrap();
+        region.total_population = region.total_population - previous as u64 + count as u64;
+        OmniModule::refresh_protection(0, region);
+    });
+}
+
+#[test]
+fn catch_weights_follow_population_pressure() {
+    new_test_ext().execute_with(|| {
+        let region = get_test_region::<Test>();
+        // the region's energy feeds 100_000 / 17 herbivores, which the model's equilibrium
+        // splits into 200 herbivores and 193 carnivores
+        assert_eq!(
+            OmniModule::carrying_capacities(&region),
+            vec![(0, 100), (1, 100), (2, 193)]
+        );
+        assert_eq!(OmniModule::population_pressure(&region, 0), Permill::one());
+        assert_eq!(
+            OmniModule::population_pressure(&region, 2),
+            Permill::from_rational(150u64, 193)
+        );
+
+        // equal populations, so the species furthest below its capacity is caught least
+        let weights = OmniModule::catch_weights(&region);
+        assert!(weights[2].1 < weights[0].1 && weights[0].1 == weights[1].1);
+        let caught = sample_region(&region, hashed_rolls(10_000));
+        assert!(caught[2] < caught[0] && caught[2] < caught[1]);
+
+        // a species at or above its capacity has its population as weight
+        let mut crowded = region.clone();
+        crowded
+            .population_demographics
+            .try_insert(2, 1_000)
+            .unwrap();
+        assert_eq!(OmniModule::population_pressure(&crowded, 2), Permill::one());
+        assert_eq!(
+            OmniModule::catch_weights(&crowded)[2],
+            (2, 1_000 * 1_000_000)
+        );
+    });
+}
+
+/// How often each species of the test region is picked by `rolls`
+fn sample_region(region: &Region<Test>, rolls: impl Iterator<Item = u64>) -> Vec<u64> {
+    let mut caught = vec![0; 3];
+    for roll in rolls {
+        caught[OmniModule::get_jokeymon_in_region(region, roll).unwrap() as usize] += 1;
+    }
+    caught
+}
+
+#[test]
+fn species_below_the_threshold_are_protected_until_they_recover() {
+    new_test_ext().execute_with(|| {
+        // 4 of a capacity of 100 is just below the mock's 5%
+        set_population(0, 4);
+        assert!(OmniPallet::ProtectedSpecies::<Test>::contains_key(0, 0));
+        System::assert_last_event(
+            Event::SpeciesProtected {
+                region_id: 0,
+                species_id: 0,
+            }
+            .into(),
+        );
+        let region = OmniPallet::RegionIdToRegion::<Test>::get(0);
+        assert_eq!(sample_region(&region, hashed_rolls(1_000))[0], 0);
+        for who in 1..6 {
+            assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(who)));
+        }
+        assert_eq!(
+            OmniPallet::RegionIdToRegion::<Test>::get(0).population_demographics[&0],
+            4
+        );
+
+        // releasing one takes it back over the threshold
+        let jokeymon_id = give_jokeymon(0, 0);
+        assert_ok!(OmniModule::release_jokeymon(
+            RuntimeOrigin::signed(0),
+            jokeymon_id
+        ));
+        assert!(!OmniPallet::ProtectedSpecies::<Test>::contains_key(0, 0));
+        assert!(System::events().iter().any(|record| matches!(
+            record.event,
+            RuntimeEvent::OmniModule(Event::SpeciesUnprotected {
+                region_id: 0,
+                species_id: 0
+            })
+        )));
+    });
+}
+
+#[test]
+fn region_of_protected_species_catch_changes_nothing() {
+    new_test_ext().execute_with(|| {
+        for species_id in 0..3 {
+            set_population(species_id, 1);
+        }
+        assert_eq!(
+            OmniPallet::ProtectedSpecies::<Test>::iter_prefix(0).count(),
+            3
+        );
+        assert_catch_fails_cleanly(0, Error::<Test>::NoCatchableJokeymon.into());
+    });
+}
+
+#[test]
+fn population_updates_refresh_protection() {
+    new_test_ext().execute_with(|| {
+        set_population(2, 1);
+        assert!(OmniPallet::ProtectedSpecies::<Test>::contains_key(0, 2));
+
+        // carnivores recover once there are plenty of herbivores to eat
+        set_population(0, 3_000);
+        OmniModule::update_populations(&PopulationModel {
+            timestep: 100,
+            ..Default::default()
+        });
+        assert!(!OmniPallet::ProtectedSpecies::<Test>::contains_key(0, 2));
+        assert!(!OmniModule::is_protected(
+            &OmniPallet::RegionIdToRegion::<Test>::get(0),
+            2
+        ));
+    });
+}
+
+// ---- Fees & Deposits ----
+
+#[test]
+fn catch_charges_fee_and_holds_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0u64)));
+
+        let held = Balances::balance_on_hold(&HoldReason::JokeymonDeposit.into(), &0u64);
+        assert_eq!(held, JokeymonDeposit::get());
+        assert_eq!(
+            Balances::free_balance(0u64),
+            INITIAL_BALANCE - CatchFee::get() - JokeymonDeposit::get()
+        );
+        assert_eq!(
+            Balances::free_balance(OmniModule::account_id()),
+            CatchFee::get()
+        );
+    });
+}
+
+#[test]
+fn catch_without_funds_fails() {
+    new_test_ext().execute_with(|| {
+        let region = OmniPallet::RegionIdToRegion::<Test>::get(0);
+        assert_noop!(
+            OmniModule::catch_jokeymon(RuntimeOrigin::signed(ENDOWED_ACCOUNTS)),
+            TokenError::FundsUnavailable
+        );
+        assert_eq!(OmniPallet::RegionIdToRegion::<Test>::get(0), region);
+    });
+}
+
+#[test]
+fn release_returns_deposit_and_repopulates_region() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0u64)));
+        let jokeymon_id = OmniPallet::AccountToData::<Test>::get(0u64).jokeymon[0];
+        let species_id = OmniPallet::JokeymonIdToData::<Test>::get(jokeymon_id)
+            .unwrap()
+            .id;
+        let population = OmniPallet::RegionIdToRegion::<Test>::get(0).total_population;
+
+        assert_ok!(OmniModule::release_jokeymon(
+            RuntimeOrigin::signed(0u64),
+            jokeymon_id
+        ));
+
+        // deposit is returned, the fee is not
+        let held = Balances::balance_on_hold(&HoldReason::JokeymonDeposit.into(), &0u64);
+        assert_eq!(held, 0);
+        assert_eq!(
+            Balances::free_balance(0u64),
+            INITIAL_BALANCE - CatchFee::get()
+        );
+
+        // jokeymon is back in the region
+        assert!(OmniPallet::AccountToData::<Test>::get(0u64)
+            .jokeymon
+            .is_empty());
+        assert!(OmniPallet::JokeymonIdToData::<Test>::get(jokeymon_id).is_none());
+        assert_eq!(
+            OmniPallet::RegionIdToRegion::<Test>::get(0).total_population,
+            population + 1
+        );
+        System::assert_last_event(
+            Event::JokeymonReleased {
+                species_id,
+                jokeymon_id,
+                region_id: 0,
+                who: 0,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn releasing_unowned_jokeymon_fails() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0u64)));
+        let jokeymon_id = OmniPallet::AccountToData::<Test>::get(0u64).jokeymon[0];
+        assert_noop!(
+            OmniModule::release_jokeymon(RuntimeOrigin::signed(1u64), jokeymon_id),
+            Error::<Test>::NotJokeymonOwner
+        );
+    });
+}
+
+#[test]
+fn travel_charges_fee() {
+    new_test_ext().execute_with(|| {
+        let mut region = get_test_region::<Test>();
+        region.id = 1;
+        OmniPallet::RegionIdToRegion::<Test>::set(1, region);
+
+        assert_ok!(OmniModule::travel(RuntimeOrigin::signed(0u64), 1));
+
+        assert_eq!(
+            OmniPallet::AccountToData::<Test>::get(0u64).current_region,
+            1
+        );
+        assert_eq!(
+            Balances::free_balance(0u64),
+            INITIAL_BALANCE - TravelFee::get()
+        );
+        assert_eq!(
+            Balances::free_balance(OmniModule::account_id()),
+            TravelFee::get()
+        );
+    });
+}
+
+#[test]
+fn travel_to_unknown_region_fails() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            OmniModule::travel(RuntimeOrigin::signed(0u64), 1),
+            Error::<Test>::UnknownRegion
+        );
+        assert_noop!(
+            OmniModule::travel(RuntimeOrigin::signed(0u64), 0),
+            Error::<Test>::AlreadyInRegion
+        );
+    });
+}
+
+// ---- Nonfungibles ----
+
+#[test]
+fn jokeymon_are_inspectable_as_nfts() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0u64)));
+        let jokeymon_id = OmniPallet::AccountToData::<Test>::get(0u64).jokeymon[0];
+        let species_id = OmniPallet::JokeymonIdToData::<Test>::get(jokeymon_id)
+            .unwrap()
+            .id;
+        let species = OmniPallet::SpeciesIdToSpeciesData::<Test>::get(species_id);
+
+        assert_eq!(
+            OmniModule::owner(&JOKEYMON_COLLECTION, &jokeymon_id),
+            Some(0)
+        );
+        assert_eq!(
+            OmniModule::attribute(&JOKEYMON_COLLECTION, &jokeymon_id, SPECIES_KEY),
+            Some(species_id.encode())
+        );
+        assert_eq!(
+            OmniModule::attribute(&JOKEYMON_COLLECTION, &jokeymon_id, DIET_KEY),
+            Some(species.diet.encode())
+        );
+        assert_eq!(OmniModule::owner(&1, &jokeymon_id), None);
+        assert_eq!(
+            OmniModule::owner(&JOKEYMON_COLLECTION, &(jokeymon_id + 1)),
+            None
+        );
+    });
+}
+
+#[test]
+fn nft_transfer_moves_jokeymon_and_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0u64)));
+        let jokeymon_id = OmniPallet::AccountToData::<Test>::get(0u64).jokeymon[0];
+
+        assert_ok!(<OmniModule as Transfer<u64>>::transfer(
+            &JOKEYMON_COLLECTION,
+            &jokeymon_id,
+            &1u64
+        ));
+
+        assert_eq!(
+            OmniModule::owner(&JOKEYMON_COLLECTION, &jokeymon_id),
+            Some(1)
+        );
+        assert!(OmniPallet::AccountToData::<Test>::get(0u64)
+            .jokeymon
+            .is_empty());
+        assert_eq!(
+            OmniPallet::AccountToData::<Test>::get(1u64).jokeymon[0],
+            jokeymon_id
+        );
+        let reason = HoldReason::JokeymonDeposit.into();
+        assert_eq!(Balances::balance_on_hold(&reason, &0u64), 0);
+        assert_eq!(
+            Balances::balance_on_hold(&reason, &1u64),
+            JokeymonDeposit::get()
+        );
+    });
+}
+
+#[test]
+fn locked_jokeymon_cannot_move() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0u64)));
+        let jokeymon_id = OmniPallet::AccountToData::<Test>::get(0u64).jokeymon[0];
+
+        assert_ok!(OmniModule::disable_transfer(
+            &JOKEYMON_COLLECTION,
+            &jokeymon_id
+        ));
+        assert!(!OmniModule::can_transfer(
+            &JOKEYMON_COLLECTION,
+            &jokeymon_id
+        ));
+        assert_noop!(
+            OmniModule::transfer_jokeymon(RuntimeOrigin::signed(0u64), 1, jokeymon_id),
+            Error::<Test>::JokeymonLocked
+        );
+        assert_noop!(
+            OmniModule::release_jokeymon(RuntimeOrigin::signed(0u64), jokeymon_id),
+            Error::<Test>::JokeymonLocked
+        );
+
+        assert_ok!(OmniModule::enable_transfer(
+            &JOKEYMON_COLLECTION,
+            &jokeymon_id
+        ));
+        assert_ok!(OmniModule::transfer_jokeymon(
+            RuntimeOrigin::signed(0u64),
+            1,
+            jokeymon_id
+        ));
+    });
+}
+
+#[test]
+fn nft_locks_leave_pallet_locks_alone() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0u64)));
+        let jokeymon_id = OmniPallet::AccountToData::<Test>::get(0u64).jokeymon[0];
+        assert_ok!(OmniModule::list_for_sale(
+            RuntimeOrigin::signed(0u64),
+            jokeymon_id,
+            100
+        ));
+
+        // a listed jokeymon can't be unlocked, relocked or moved through the trait
+        assert_noop!(
+            OmniModule::enable_transfer(&JOKEYMON_COLLECTION, &jokeymon_id),
+            Error::<Test>::JokeymonLocked
+        );
+        assert_noop!(
+            OmniModule::disable_transfer(&JOKEYMON_COLLECTION, &jokeymon_id),
+            Error::<Test>::JokeymonLocked
+        );
+        assert_noop!(
+            <OmniModule as Transfer<u64>>::transfer(&JOKEYMON_COLLECTION, &jokeymon_id, &1u64),
+            Error::<Test>::JokeymonLocked
+        );
+        assert_eq!(
+            OmniPallet::LockedJokeymon::<Test>::get(jokeymon_id),
+            Some(LockReason::Listing)
+        );
+
+        // once the listing is gone the trait lock works as before
+        assert_ok!(OmniModule::cancel_listing(
+            RuntimeOrigin::signed(0u64),
+            jokeymon_id
+        ));
+        assert_ok!(OmniModule::disable_transfer(
+            &JOKEYMON_COLLECTION,
+            &jokeymon_id
+        ));
+        assert_noop!(
+            OmniModule::list_for_sale(RuntimeOrigin::signed(0u64), jokeymon_id, 100),
+            Error::<Test>::JokeymonLocked
+        );
+        assert_ok!(OmniModule::enable_transfer(
+            &JOKEYMON_COLLECTION,
+            &jokeymon_id
+        ));
+        assert!(OmniModule::can_transfer(&JOKEYMON_COLLECTION, &jokeymon_id));
+    });
+}
+
+#[test]
+fn transferring_unowned_jokeymon_fails() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0u64)));
+        let jokeymon_id = OmniPallet::AccountToData::<Test>::get(0u64).jokeymon[0];
+        assert_noop!(
+            OmniModule::transfer_jokeymon(RuntimeOrigin::signed(1u64), 2, jokeymon_id),
+            Error::<Test>::NotJokeymonOwner
+        );
+    });
+}
+
+// ---- Marketplace ----
+
+/// Catches a jokeymon for `who` and returns its id
+fn catch_one(who: u64) -> JokeymonId {
+    assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(who)));
+    *OmniPallet::AccountToData::<Test>::get(who)
+        .jokeymon
+        .last()
+        .unwrap()
+}
+
+#[test]
+fn listing_locks_jokeymon() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        assert_ok!(OmniModule::list_for_sale(
+            RuntimeOrigin::signed(0),
+            jokeymon_id,
+            1_000
+        ));
+
+        assert_noop!(
+            OmniModule::list_for_sale(RuntimeOrigin::signed(0), jokeymon_id, 500),
+            Error::<Test>::AlreadyListed
+        );
+        assert_noop!(
+            OmniModule::transfer_jokeymon(RuntimeOrigin::signed(0), 1, jokeymon_id),
+            Error::<Test>::JokeymonLocked
+        );
+        System::assert_last_event(
+            Event::JokeymonListed {
+                jokeymon_id,
+                seller: 0,
+                price: 1_000,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn listing_unowned_jokeymon_fails() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        assert_noop!(
+            OmniModule::list_for_sale(RuntimeOrigin::signed(1), jokeymon_id, 1_000),
+            Error::<Test>::NotJokeymonOwner
+        );
+    });
+}
+
+#[test]
+fn cancel_listing_unlocks_jokeymon() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        assert_ok!(OmniModule::list_for_sale(
+            RuntimeOrigin::signed(0),
+            jokeymon_id,
+            1_000
+        ));
+
+        assert_noop!(
+            OmniModule::cancel_listing(RuntimeOrigin::signed(1), jokeymon_id),
+            Error::<Test>::NotJokeymonOwner
+        );
+        assert_ok!(OmniModule::cancel_listing(
+            RuntimeOrigin::signed(0),
+            jokeymon_id
+        ));
+
+        assert!(OmniPallet::Listings::<Test>::get(jokeymon_id).is_none());
+        assert_noop!(
+            OmniModule::buy(RuntimeOrigin::signed(1), jokeymon_id),
+            Error::<Test>::NotListed
+        );
+        assert_ok!(OmniModule::transfer_jokeymon(
+            RuntimeOrigin::signed(0),
+            1,
+            jokeymon_id
+        ));
+    });
+}
+
+#[test]
+fn buy_pays_seller_and_treasury() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        let price = 1_000;
+        assert_ok!(OmniModule::list_for_sale(
+            RuntimeOrigin::signed(0),
+            jokeymon_id,
+            price
+        ));
+        let seller_before = Balances::free_balance(0u64);
+        let buyer_before = Balances::free_balance(1u64);
+
+        assert_ok!(OmniModule::buy(RuntimeOrigin::signed(1), jokeymon_id));
+
+        let fee = MarketplaceFee::get().mul_floor(price);
+        assert_eq!(Balances::free_balance(TreasuryAccount::get()), fee);
+        // the seller also gets their deposit back
+        assert_eq!(
+            Balances::free_balance(0u64),
+            seller_before + price - fee + JokeymonDeposit::get()
+        );
+        assert_eq!(
+            Balances::free_balance(1u64),
+            buyer_before - price - JokeymonDeposit::get()
+        );
+        assert_eq!(
+            OmniModule::owner(&JOKEYMON_COLLECTION, &jokeymon_id),
+            Some(1)
+        );
+        assert!(OmniPallet::Listings::<Test>::get(jokeymon_id).is_none());
+        assert!(OmniModule::can_transfer(&JOKEYMON_COLLECTION, &jokeymon_id));
+        System::assert_last_event(
+            Event::JokeymonSold {
+                jokeymon_id,
+                seller: 0,
+                buyer: 1,
+                price,
+                fee,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn buy_fails_without_funds_or_from_seller() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        assert_ok!(OmniModule::list_for_sale(
+            RuntimeOrigin::signed(0),
+            jokeymon_id,
+            INITIAL_BALANCE * 2
+        ));
+
+        assert_noop!(
+            OmniModule::buy(RuntimeOrigin::signed(0), jokeymon_id),
+            Error::<Test>::CannotBuyOwnJokeymon
+        );
+        assert_noop!(
+            OmniModule::buy(RuntimeOrigin::signed(1), jokeymon_id),
+            TokenError::FundsUnavailable
+        );
+        assert!(OmniPallet::Listings::<Test>::get(jokeymon_id).is_some());
+    });
+}
+
+/// Move a locked jokeymon to another account, as only a broken lock would let happen
+fn force_move(jokeymon_id: JokeymonId, from: u64, to: u64) {
+    OmniPallet::LockedJokeymon::<Test>::remove(jokeymon_id);
+    assert_ok!(OmniModule::transfer_jokeymon(
+        RuntimeOrigin::signed(from),
+        to,
+        jokeymon_id
+    ));
+}
+
+#[test]
+fn buy_fails_once_the_seller_no_longer_owns_the_jokeymon() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        assert_ok!(OmniModule::list_for_sale(
+            RuntimeOrigin::signed(0),
+            jokeymon_id,
+            1_000
+        ));
+        force_move(jokeymon_id, 0, 2);
+
+        // the buyer pays nobody and takes nothing
+        assert_noop!(
+            OmniModule::buy(RuntimeOrigin::signed(1), jokeymon_id),
+            Error::<Test>::NotJokeymonOwner
+        );
+        assert_eq!(
+            OmniModule::owner(&JOKEYMON_COLLECTION, &jokeymon_id),
+            Some(2)
+        );
+    });
+}
+
+// ---- Auctions ----
+
+fn english(min_bid: u64) -> AuctionKind<u64> {
+    AuctionKind::English { min_bid }
+}
+
+fn held_bid(who: u64) -> u64 {
+    Balances::balance_on_hold(&HoldReason::AuctionBid.into(), &who)
+}
+
+#[test]
+fn create_auction_validates_and_locks() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        assert_noop!(
+            OmniModule::create_auction(RuntimeOrigin::signed(1), jokeymon_id, english(10), 10),
+            Error::<Test>::NotJokeymonOwner
+        );
+        assert_noop!(
+            OmniModule::create_auction(RuntimeOrigin::signed(0), jokeymon_id, english(10), 0),
+            Error::<Test>::InvalidAuction
+        );
+        assert_noop!(
+            OmniModule::create_auction(
+                RuntimeOrigin::signed(0),
+                jokeymon_id,
+                AuctionKind::Dutch {
+                    start_price: 10,
+                    floor_price: 20
+                },
+                10
+            ),
+            Error::<Test>::InvalidAuction
+        );
+
+        assert_ok!(OmniModule::create_auction(
+            RuntimeOrigin::signed(0),
+            jokeymon_id,
+            english(10),
+            10
+        ));
+        assert_eq!(
+            OmniPallet::AuctionQueue::<Test>::get().into_inner(),
+            vec![(11, jokeymon_id)]
+        );
+        assert_noop!(
+            OmniModule::list_for_sale(RuntimeOrigin::signed(0), jokeymon_id, 100),
+            Error::<Test>::JokeymonLocked
+        );
+    });
+}
+
+#[test]
+fn outbid_bidders_are_refunded() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        assert_ok!(OmniModule::create_auction(
+            RuntimeOrigin::signed(0),
+            jokeymon_id,
+            english(100),
+            10
+        ));
+
+        assert_noop!(
+            OmniModule::bid(RuntimeOrigin::signed(1), jokeymon_id, 99),
+            Error::<Test>::BidTooLow
+        );
+        assert_noop!(
+            OmniModule::bid(RuntimeOrigin::signed(0), jokeymon_id, 100),
+            Error::<Test>::CannotBuyOwnJokeymon
+        );
+        assert_ok!(OmniModule::bid(RuntimeOrigin::signed(1), jokeymon_id, 100));
+        assert_eq!(held_bid(1), 100);
+
+        assert_noop!(
+            OmniModule::bid(RuntimeOrigin::signed(2), jokeymon_id, 100),
+            Error::<Test>::BidTooLow
+        );
+        assert_ok!(OmniModule::bid(RuntimeOrigin::signed(2), jokeymon_id, 150));
+        assert_eq!(held_bid(1), 0);
+        assert_eq!(held_bid(2), 150);
+        assert_eq!(Balances::free_balance(1u64), INITIAL_BALANCE);
+    });
+}
+
+#[test]
+fn english_auction_settles_on_idle() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        assert_ok!(OmniModule::create_auction(
+            RuntimeOrigin::signed(0),
+            jokeymon_id,
+            english(100),
+            10
+        ));
+        assert_ok!(OmniModule::bid(
+            RuntimeOrigin::signed(1),
+            jokeymon_id,
+            1_000
+        ));
+        let seller_before = Balances::free_balance(0u64);
+
+        // nothing happens before the end
+        OmniModule::on_idle(10, Weight::MAX);
+        assert!(OmniPallet::Auctions::<Test>::get(jokeymon_id).is_some());
+
+        System::set_block_number(11);
+        assert_noop!(
+            OmniModule::bid(RuntimeOrigin::signed(2), jokeymon_id, 2_000),
+            Error::<Test>::AuctionEnded
+        );
+        OmniModule::on_idle(11, Weight::MAX);
+
+        let fee = MarketplaceFee::get().mul_floor(1_000);
+        assert!(OmniPallet::Auctions::<Test>::get(jokeymon_id).is_none());
+        assert!(OmniPallet::AuctionQueue::<Test>::get().is_empty());
+        assert_eq!(
+            OmniModule::owner(&JOKEYMON_COLLECTION, &jokeymon_id),
+            Some(1)
+        );
+        assert!(OmniModule::can_transfer(&JOKEYMON_COLLECTION, &jokeymon_id));
+        assert_eq!(held_bid(1), 0);
+        assert_eq!(
+            Balances::free_balance(0u64),
+            seller_before + 1_000 - fee + JokeymonDeposit::get()
+        );
+        assert_eq!(Balances::free_balance(TreasuryAccount::get()), fee);
+        System::assert_last_event(
+            Event::AuctionSettled {
+                jokeymon_id,
+                seller: 0,
+                buyer: 1,
+                price: 1_000,
+                fee,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn unsold_auction_returns_jokeymon() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        assert_ok!(OmniModule::create_auction(
+            RuntimeOrigin::signed(0),
+            jokeymon_id,
+            english(100),
+            5
+        ));
+
+        OmniModule::on_idle(6, Weight::MAX);
+
+        assert_eq!(
+            OmniModule::owner(&JOKEYMON_COLLECTION, &jokeymon_id),
+            Some(0)
+        );
+        assert!(OmniModule::can_transfer(&JOKEYMON_COLLECTION, &jokeymon_id));
+        System::assert_last_event(
+            Event::AuctionUnsold {
+                jokeymon_id,
+                seller: 0,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn auction_settlement_refunds_once_the_seller_no_longer_owns_the_jokeymon() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        assert_ok!(OmniModule::create_auction(
+            RuntimeOrigin::signed(0),
+            jokeymon_id,
+            english(100),
+            10
+        ));
+        assert_ok!(OmniModule::bid(
+            RuntimeOrigin::signed(1),
+            jokeymon_id,
+            1_000
+        ));
+        force_move(jokeymon_id, 0, 2);
+        let seller_before = Balances::free_balance(0u64);
+        let bidder_before = Balances::free_balance(1u64);
+
+        OmniModule::on_idle(11, Weight::MAX);
+
+        assert_eq!(
+            OmniModule::owner(&JOKEYMON_COLLECTION, &jokeymon_id),
+            Some(2)
+        );
+        assert_eq!(held_bid(1), 0);
+        assert_eq!(Balances::free_balance(1u64), bidder_before + 1_000);
+        assert_eq!(Balances::free_balance(0u64), seller_before);
+        System::assert_has_event(
+            Event::BidRefunded {
+                jokeymon_id,
+                bidder: 1,
+                amount: 1_000,
+            }
+            .into(),
+        );
+        System::assert_last_event(
+            Event::AuctionUnsold {
+                jokeymon_id,
+                seller: 0,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn dutch_bid_fails_once_the_seller_no_longer_owns_the_jokeymon() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        assert_ok!(OmniModule::create_auction(
+            RuntimeOrigin::signed(0),
+            jokeymon_id,
+            AuctionKind::Dutch {
+                start_price: 1_000,
+                floor_price: 100,
+            },
+            10
+        ));
+        force_move(jokeymon_id, 0, 2);
+
+        assert_noop!(
+            OmniModule::bid(RuntimeOrigin::signed(1), jokeymon_id, 1_000),
+            Error::<Test>::NotJokeymonOwner
+        );
+    });
+}
+
+#[test]
+fn on_idle_respects_weight_limit() {
+    new_test_ext().execute_with(|| {
+        let first = catch_one(0);
+        let second = catch_one(0);
+        for jokeymon_id in [first, second] {
+            assert_ok!(OmniModule::create_auction(
+                RuntimeOrigin::signed(0),
+                jokeymon_id,
+                english(100),
+                5
+            ));
+        }
+
+        // not even enough weight to read the queue
+        assert_eq!(OmniModule::on_idle(6, Weight::zero()), Weight::zero());
+        assert_eq!(OmniPallet::AuctionQueue::<Test>::get().len(), 2);
+
+        // enough weight for a single settlement
+        let db_weight = <Test as frame_system::Config>::DbWeight::get();
+        let single = Weight::from_parts(10_000, 0).saturating_add(db_weight.reads_writes(7, 10));
+        OmniModule::on_idle(6, single);
+        assert_eq!(
+            OmniPallet::AuctionQueue::<Test>::get().into_inner(),
+            vec![(6, second)]
+        );
+        assert!(OmniPallet::Auctions::<Test>::get(first).is_none());
+
+        OmniModule::on_idle(6, Weight::MAX);
+        assert!(OmniPallet::AuctionQueue::<Test>::get().is_empty());
+    });
+}
+
+#[test]
+fn dutch_auction_price_decays() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = catch_one(0);
+        assert_ok!(OmniModule::create_auction(
+            RuntimeOrigin::signed(0),
+            jokeymon_id,
+            AuctionKind::Dutch {
+                start_price: 1_000,
+                floor_price: 200
+            },
+            10
+        ));
+        let auction = OmniPallet::Auctions::<Test>::get(jokeymon_id).unwrap();
+        assert_eq!(OmniModule::dutch_auction_price(&auction, 1), 1_000);
+        assert_eq!(OmniModule::dutch_auction_price(&auction, 6), 600);
+        assert_eq!(OmniModule::dutch_auction_price(&auction, 11), 200);
+
+        System::set_block_number(6);
+        assert_noop!(
+            OmniModule::bid(RuntimeOrigin::signed(1), jokeymon_id, 599),
+            Error::<Test>::BidTooLow
+        );
+        let buyer_before = Balances::free_balance(1u64);
+        assert_ok!(OmniModule::bid(
+            RuntimeOrigin::signed(1),
+            jokeymon_id,
+            1_000
+        ));
+
+        // pays the current price, not the bid
+        assert_eq!(
+            Balances::free_balance(1u64),
+            buyer_before - 600 - JokeymonDeposit::get()
+        );
+        assert_eq!(
+            OmniModule::owner(&JOKEYMON_COLLECTION, &jokeymon_id),
+            Some(1)
+        );
+        assert!(OmniPallet::Auctions::<Test>::get(jokeymon_id).is_none());
+        assert!(OmniPallet::AuctionQueue::<Test>::get().is_empty());
+    });
+}
+
+// ---- Battles ----
+
+fn team(ids: &[JokeymonId]) -> Team<Test> {
+    Team::<Test>::try_from(ids.to_vec()).unwrap()
+}
+
+fn held_wager(who: u64) -> u64 {
+    Balances::balance_on_hold(&HoldReason::BattleWager.into(), &who)
+}
+
+#[test]
+fn battles_are_deterministic() {
+    let strong = Fighter {
+        health: 300,
+        attack: 60,
+    };
+    let weak = Fighter {
+        health: 100,
+        attack: 10,
+    };
+
+    // mirrored teams with even rolls draw
+    let result = battle::resolve(vec![strong.clone()], vec![strong.clone()], 100, |_| 20);
+    assert_eq!(result.outcome, BattleOutcome::Draw);
+
+    // the stronger team wins no matter the rolls
+    for roll in [0u8, 40, 255] {
+        let result = battle::resolve(vec![weak.clone()], vec![strong.clone()], 100, |_| roll);
+        assert_eq!(result.outcome, BattleOutcome::SecondWins);
+    }
+
+    // the next jokeymon in line steps in when the front one faints
+    let wounded = Fighter {
+        health: 200,
+        attack: 60,
+    };
+    let result = battle::resolve(
+        vec![weak.clone(), strong.clone()],
+        vec![wounded],
+        100,
+        |_| 20,
+    );
+    assert_eq!(result.outcome, BattleOutcome::FirstWins);
+
+    // out of rounds, the healthiest team wins
+    let result = battle::resolve(vec![strong.clone()], vec![weak], 1, |_| 20);
+    assert_eq!(result.outcome, BattleOutcome::FirstWins);
+    assert_eq!(result.rounds, 1);
+}
+
+#[test]
+fn challenge_validates_team() {
+    new_test_ext().execute_with(|| {
+        let mine = give_jokeymon(0, 0);
+        let theirs = give_jokeymon(1, 0);
+
+        assert_noop!(
+            OmniModule::challenge(RuntimeOrigin::signed(0), 1, team(&[]), 0),
+            Error::<Test>::InvalidTeam
+        );
+        assert_noop!(
+            OmniModule::challenge(RuntimeOrigin::signed(0), 1, team(&[theirs]), 0),
+            Error::<Test>::InvalidTeam
+        );
+        assert_noop!(
+            OmniModule::challenge(RuntimeOrigin::signed(0), 1, team(&[mine, mine]), 0),
+            Error::<Test>::InvalidTeam
+        );
+        assert_noop!(
+            OmniModule::challenge(RuntimeOrigin::signed(0), 0, team(&[mine]), 0),
+            Error::<Test>::CannotChallengeSelf
+        );
+
+        // boxed jokeymon stay out of battles
+        assert_ok!(OmniModule::deposit_to_box(RuntimeOrigin::signed(0), mine));
+        assert_noop!(
+            OmniModule::challenge(RuntimeOrigin::signed(0), 1, team(&[mine]), 0),
+            Error::<Test>::InvalidTeam
+        );
+    });
+}
+
+#[test]
+fn challenge_locks_team_and_holds_wager() {
+    new_test_ext().execute_with(|| {
+        let mine = give_jokeymon(0, 0);
+        let other = give_jokeymon(2, 0);
+        assert_ok!(OmniModule::challenge(
+            RuntimeOrigin::signed(0),
+            1,
+            team(&[mine]),
+            100
+        ));
+
+        assert_eq!(held_wager(0), 100);
+        assert!(!OmniModule::can_transfer(&JOKEYMON_COLLECTION, &mine));
+        assert_noop!(
+            OmniModule::challenge(RuntimeOrigin::signed(2), 1, team(&[other]), 0),
+            Error::<Test>::AlreadyChallenged
+        );
+        System::assert_last_event(
+            Event::ChallengeIssued {
+                challenger: 0,
+                opponent: 1,
+                wager: 100,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn winner_takes_the_wager() {
+    new_test_ext().execute_with(|| {
+        let strong = team(&[give_jokeymon(0, 2), give_jokeymon(0, 2)]);
+        let weak = team(&[give_jokeymon(1, 0)]);
+        assert_ok!(OmniModule::challenge(
+            RuntimeOrigin::signed(0),
+            1,
+            strong,
+            100
+        ));
+
+        assert_noop!(
+            OmniModule::accept_challenge(RuntimeOrigin::signed(2), weak.clone()),
+            Error::<Test>::ChallengeNotFound
+        );
+        assert_ok!(OmniModule::accept_challenge(RuntimeOrigin::signed(1), weak));
+
+        assert_eq!(held_wager(0), 0);
+        assert_eq!(held_wager(1), 0);
+        assert_eq!(Balances::free_balance(0u64), INITIAL_BALANCE + 100);
+        assert_eq!(Balances::free_balance(1u64), INITIAL_BALANCE - 100);
+        assert_eq!(OmniPallet::BattleRecords::<Test>::get(0u64).wins, 1);
+        assert_eq!(OmniPallet::BattleRecords::<Test>::get(1u64).losses, 1);
+        assert!(OmniPallet::Challenges::<Test>::get(1u64).is_none());
+        assert!(System::events().iter().any(|record| matches!(
+            record.event,
+            RuntimeEvent::OmniModule(Event::BattleResolved {
+                winner: Some(0),
+                wager: 100,
+                ..
+            })
+        )));
+    });
+}
+
+#[test]
+fn winners_party_shares_the_experience() {
+    new_test_ext().execute_with(|| {
+        let fighters = [give_jokeymon(0, 2), give_jokeymon(0, 2)];
+        let benched = give_jokeymon(0, 0);
+        let boxed = give_jokeymon(0, 0);
+        assert_ok!(OmniModule::deposit_to_box(RuntimeOrigin::signed(0), boxed));
+        let loser = give_jokeymon(1, 0);
+        assert_ok!(OmniModule::challenge(
+            RuntimeOrigin::signed(0),
+            1,
+            team(&fighters),
+            0
+        ));
+        assert_ok!(OmniModule::accept_challenge(
+            RuntimeOrigin::signed(1),
+            team(&[loser])
+        ));
+
+        // the whole party shares it, whether it fought or not
+        let share = BattleExperience::get() / 3;
+        for id in [fighters[0], fighters[1], benched] {
+            assert_eq!(OmniPallet::Experience::<Test>::get(id), share);
+        }
+        assert_eq!(OmniPallet::Experience::<Test>::get(boxed), 0);
+        assert_eq!(OmniPallet::Experience::<Test>::get(loser), 0);
+        System::assert_has_event(
+            Event::ExperienceShared {
+                who: 0,
+                party: team(&[fighters[0], fighters[1], benched]),
+                amount: share,
+            }
+            .into(),
+        );
+
+        // and it is gone once the jokeymon is released
+        assert_ok!(OmniModule::release_jokeymon(
+            RuntimeOrigin::signed(0),
+            benched
+        ));
+        assert_eq!(OmniPallet::Experience::<Test>::get(benched), 0);
+    });
+}
+
+#[test]
+fn experience_strengthens_fighters_up_to_a_cap() {
+    let fighter = Fighter {
+        health: 200,
+        attack: 40,
+    };
+    assert_eq!(fighter.clone().with_experience(0), fighter);
+    assert_eq!(
+        fighter.clone().with_experience(100),
+        Fighter {
+            health: 220,
+            attack: 44,
+        }
+    );
+    let capped = Fighter {
+        health: 300,
+        attack: 60,
+    };
+    assert_eq!(fighter.clone().with_experience(500), capped);
+    assert_eq!(fighter.with_experience(u32::MAX), capped);
+}
+
+#[test]
+fn accepting_without_funds_fails() {
+    new_test_ext().execute_with(|| {
+        let mine = give_jokeymon(0, 0);
+        let poor = ENDOWED_ACCOUNTS;
+        let theirs = give_jokeymon(poor, 0);
+        assert_ok!(OmniModule::challenge(
+            RuntimeOrigin::signed(0),
+            poor,
+            team(&[mine]),
+            100
+        ));
+        assert_noop!(
+            OmniModule::accept_challenge(RuntimeOrigin::signed(poor), team(&[theirs])),
+            TokenError::FundsUnavailable
+        );
+    });
+}
+
+#[test]
+fn cancel_challenge_releases_team_and_wager() {
+    new_test_ext().execute_with(|| {
+        let mine = give_jokeymon(0, 0);
+        assert_ok!(OmniModule::challenge(
+            RuntimeOrigin::signed(0),
+            1,
+            team(&[mine]),
+            100
+        ));
+
+        assert_noop!(
+            OmniModule::cancel_challenge(RuntimeOrigin::signed(2), 1),
+            Error::<Test>::ChallengeNotFound
+        );
+        // the opponent declines
+        assert_ok!(OmniModule::cancel_challenge(RuntimeOrigin::signed(1), 1));
+
+        assert_eq!(held_wager(0), 0);
+        assert_eq!(Balances::free_balance(0u64), INITIAL_BALANCE);
+        assert!(OmniModule::can_transfer(&JOKEYMON_COLLECTION, &mine));
+        assert!(OmniPallet::Challenges::<Test>::get(1u64).is_none());
+    });
+}
+
+// ---- Rated Ladder ----
+
+#[test]
+fn elo_is_zero_sum_and_favours_upsets() {
+    assert_eq!(
+        rating::expected_score(1_000, 1_000),
+        Permill::from_percent(50)
+    );
+    assert!(rating::expected_score(1_400, 1_000) > Permill::from_percent(90));
+    assert_eq!(
+        rating::expected_score(1_000, 1_400) + rating::expected_score(1_400, 1_000),
+        Permill::one()
+    );
+
+    // even match
+    assert_eq!(rating::updated_rating(1_000, 1_000, Score::Win), 1_016);
+    assert_eq!(rating::updated_rating(1_000, 1_000, Score::Loss), 984);
+    assert_eq!(rating::updated_rating(1_000, 1_000, Score::Draw), 1_000);
+
+    // beating a stronger player is worth more than beating a weaker one
+    let upset = rating::updated_rating(1_000, 1_400, Score::Win) - 1_000;
+    let expected = rating::updated_rating(1_400, 1_000, Score::Win) - 1_400;
+    assert!(upset > expected);
+    // ratings never go below zero
+    assert_eq!(rating::updated_rating(0, 2_000, Score::Loss), 0);
+}
+
+/// Put accounts on the rated ladder
+fn join_ladder(accounts: &[u64]) {
+    for who in accounts {
+        assert_ok!(OmniModule::join_ladder(RuntimeOrigin::signed(*who)));
+    }
+}
+
+#[test]
+fn rated_match_updates_ratings_and_leaderboard() {
+    new_test_ext().execute_with(|| {
+        give_jokeymon(0, 2);
+        give_jokeymon(0, 2);
+        give_jokeymon(1, 0);
+        join_ladder(&[0, 1, 2]);
+
+        assert_noop!(
+            OmniModule::rated_match(RuntimeOrigin::signed(0), 2),
+            Error::<Test>::NoJokeymonToBattle
+        );
+        assert_noop!(
+            OmniModule::rated_match(RuntimeOrigin::signed(0), 0),
+            Error::<Test>::CannotChallengeSelf
+        );
+        assert_ok!(OmniModule::rated_match(RuntimeOrigin::signed(0), 1));
+
+        assert_eq!(
+            OmniPallet::Ratings::<Test>::get(0, 0u64),
+            INITIAL_RATING + 16
+        );
+        assert_eq!(
+            OmniPallet::Ratings::<Test>::get(0, 1u64),
+            INITIAL_RATING - 16
+        );
+        assert_eq!(
+            OmniModule::leaderboard_page(0, 10),
+            vec![(0, INITIAL_RATING + 16), (1, INITIAL_RATING - 16)]
+        );
+        System::assert_last_event(
+            Event::RatedMatchPlayed {
+                player: 0,
+                opponent: 1,
+                winner: Some(0),
+                player_rating: INITIAL_RATING + 16,
+                opponent_rating: INITIAL_RATING - 16,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn rated_matches_need_the_ladder_and_a_cooldown() {
+    new_test_ext().execute_with(|| {
+        give_jokeymon(0, 2);
+        give_jokeymon(1, 0);
+
+        // both accounts have to opt in
+        assert_noop!(
+            OmniModule::rated_match(RuntimeOrigin::signed(0), 1),
+            Error::<Test>::NotOnLadder
+        );
+        join_ladder(&[0]);
+        assert_noop!(
+            OmniModule::join_ladder(RuntimeOrigin::signed(0)),
+            Error::<Test>::AlreadyOnLadder
+        );
+        assert_noop!(
+            OmniModule::rated_match(RuntimeOrigin::signed(0), 1),
+            Error::<Test>::NotOnLadder
+        );
+        join_ladder(&[1]);
+        System::assert_last_event(Event::LadderJoined { who: 1 }.into());
+        assert_ok!(OmniModule::rated_match(RuntimeOrigin::signed(0), 1));
+
+        // the pair waits out the cooldown whoever starts the match
+        assert_noop!(
+            OmniModule::rated_match(RuntimeOrigin::signed(0), 1),
+            Error::<Test>::RatedMatchTooSoon
+        );
+        assert_noop!(
+            OmniModule::rated_match(RuntimeOrigin::signed(1), 0),
+            Error::<Test>::RatedMatchTooSoon
+        );
+        System::set_block_number(1 + RatedMatchCooldown::get());
+        assert_ok!(OmniModule::rated_match(RuntimeOrigin::signed(1), 0));
+
+        // leaving the ladder stops matches but keeps the rating
+        let rating = OmniPallet::Ratings::<Test>::get(0, 1u64);
+        assert_ok!(OmniModule::leave_ladder(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::LadderLeft { who: 1 }.into());
+        assert_noop!(
+            OmniModule::leave_ladder(RuntimeOrigin::signed(1)),
+            Error::<Test>::NotOnLadder
+        );
+        System::set_block_number(1 + 2 * RatedMatchCooldown::get());
+        assert_noop!(
+            OmniModule::rated_match(RuntimeOrigin::signed(0), 1),
+            Error::<Test>::NotOnLadder
+        );
+        assert_eq!(OmniPallet::Ratings::<Test>::get(0, 1u64), rating);
+    });
+}
+
+#[test]
+fn leaderboard_is_bounded_and_sorted() {
+    new_test_ext().execute_with(|| {
+        for (who, rating) in [(0u64, 900), (1, 1_200), (2, 1_000), (3, 1_100)] {
+            OmniModule::update_leaderboard(&who, rating);
+        }
+        assert_eq!(
+            OmniPallet::Leaderboard::<Test>::get().into_inner(),
+            vec![(1, 1_200), (3, 1_100), (2, 1_000)]
+        );
+
+        // an account moves rather than appearing twice
+        OmniModule::update_leaderboard(&2, 1_300);
+        assert_eq!(
+            OmniPallet::Leaderboard::<Test>::get().into_inner(),
+            vec![(2, 1_300), (1, 1_200), (3, 1_100)]
+        );
+
+        assert_eq!(OmniModule::leaderboard_page(1, 2), vec![(3, 1_100)]);
+        assert!(OmniModule::leaderboard_page(2, 2).is_empty());
+    });
+}
+
+/// Give an account a rating this season, as a rated match would
+fn set_rating(who: u64, rating: Rating) {
+    OmniPallet::Ratings::<Test>::insert(OmniPallet::CurrentSeason::<Test>::get(), who, rating);
+    OmniModule::update_leaderboard(&who, rating);
+}
+
+#[test]
+fn season_end_reads_only_the_leaderboard() {
+    new_test_ext().execute_with(|| {
+        for (who, rating) in [(0u64, 1_200), (1, 1_150), (2, 1_100), (3, 1_050)] {
+            set_rating(who, rating);
+        }
+        // 3 fell off the full board, and stays off when 1 drops below it
+        set_rating(1, 900);
+        let board = OmniPallet::Leaderboard::<Test>::get();
+        assert_eq!(board.to_vec(), vec![(0, 1_200), (2, 1_100), (1, 900)]);
+
+        let weight = OmniModule::end_season_if_due(SeasonLength::get());
+
+        assert_eq!(OmniPallet::SeasonStandings::<Test>::get(0), Some(board));
+        // the season and the board, then a reward for each account on it
+        assert_eq!(
+            weight,
+            <Test as frame_system::Config>::DbWeight::get().reads_writes(2 + 3 * 2, 3 + 3 * 2)
+        );
+    });
+}
+
+#[test]
+fn season_end_snapshots_and_rewards_leaderboard() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::mint_into(&OmniModule::account_id(), 1_000));
+        set_rating(0, 1_100);
+        set_rating(1, 1_050);
+
+        // not a season boundary
+        OmniModule::on_initialize(SeasonLength::get() - 1);
+        assert_eq!(OmniPallet::CurrentSeason::<Test>::get(), 0);
+
+        OmniModule::on_initialize(SeasonLength::get());
+
+        let share = SeasonReward::get() / 2;
+        assert_eq!(OmniPallet::CurrentSeason::<Test>::get(), 1);
+        assert!(OmniPallet::Leaderboard::<Test>::get().is_empty());
+        assert_eq!(
+            OmniPallet::SeasonStandings::<Test>::get(0)
+                .unwrap()
+                .into_inner(),
+            vec![(0, 1_100), (1, 1_050)]
+        );
+        assert_eq!(Balances::free_balance(0u64), INITIAL_BALANCE + share);
+        assert_eq!(Balances::free_balance(1u64), INITIAL_BALANCE + share);
+        assert_eq!(
+            Balances::free_balance(OmniModule::account_id()),
+            1_000 - SeasonReward::get()
+        );
+        System::assert_last_event(Event::SeasonEnded { season: 0 }.into());
+
+        // new season ratings start fresh
+        assert_eq!(OmniPallet::Ratings::<Test>::get(1, 0u64), INITIAL_RATING);
+    });
+}
+
+// ---- Pokedex ----
+
+#[test]
+fn species_bitset_tracks_species() {
+    let mut bitset = SpeciesBitset::<Test>::default();
+    assert!(pokedex::insert::<Test>(&mut bitset, 0));
+    assert!(pokedex::insert::<Test>(&mut bitset, 9));
+    assert!(!pokedex::insert::<Test>(&mut bitset, 9));
+    // out of range ids are ignored
+    assert!(!pokedex::insert::<Test>(&mut bitset, MaxSpecies::get()));
+
+    assert!(pokedex::contains::<Test>(&bitset, 0));
+    assert!(pokedex::contains::<Test>(&bitset, 9));
+    assert!(!pokedex::contains::<Test>(&bitset, 1));
+    assert!(!pokedex::contains::<Test>(&bitset, 100));
+    assert_eq!(pokedex::count::<Test>(&bitset), 2);
+    assert_eq!(bitset.len(), 2);
+}
+
+#[test]
+fn catching_fills_pokedex_and_pays_milestones() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::mint_into(&OmniModule::account_id(), 1_000));
+        let who = 0;
+
+        // the mock always catches the first species left in the region
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(who)));
+        let pokedex = OmniPallet::Pokedexes::<Test>::get(who);
+        assert!(pokedex.has_seen(0) && pokedex.has_caught(0));
+        assert_eq!(pokedex.caught_count(), 1);
+
+        // a second of the same species is no progress
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(who)));
+        assert_eq!(OmniPallet::Pokedexes::<Test>::get(who).caught_count(), 1);
+        assert_eq!(
+            Balances::free_balance(OmniModule::account_id()),
+            1_000 + 2 * CatchFee::get()
+        );
+
+        OmniPallet::RegionIdToRegion::<Test>::mutate(0, |region| {
+            OmniModule::decrement_species_in_population(region, 0, 150)
+        });
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(who)));
+        assert!(OmniPallet::Pokedexes::<Test>::get(who).has_caught(1));
+
+        System::assert_has_event(
+            Event::PokedexMilestoneReached {
+                who,
+                caught: 2,
+                reward: PokedexReward::get(),
+            }
+            .into(),
+        );
+        assert_eq!(
+            Balances::free_balance(OmniModule::account_id()),
+            1_000 + 3 * CatchFee::get() - PokedexReward::get()
+        );
+    });
+}
+
+#[test]
+fn milestone_without_funds_pays_nothing() {
+    new_test_ext().execute_with(|| {
+        OmniModule::record_caught(&0, 0);
+        OmniModule::record_caught(&0, 1);
+        System::assert_last_event(
+            Event::PokedexMilestoneReached {
+                who: 0,
+                caught: 2,
+                reward: 0,
+            }
+            .into(),
+        );
+        assert_eq!(Balances::free_balance(0u64), INITIAL_BALANCE);
+
+        // catching the same species again is not progress
+        System::reset_events();
+        OmniModule::record_caught(&0, 1);
+        assert!(System::events().is_empty());
+    });
+}
+
+#[test]
+fn battles_mark_opponents_as_seen() {
+    new_test_ext().execute_with(|| {
+        give_jokeymon(0, 2);
+        give_jokeymon(1, 0);
+        join_ladder(&[0, 1]);
+        assert_ok!(OmniModule::rated_match(RuntimeOrigin::signed(0), 1));
+
+        let pokedex = OmniPallet::Pokedexes::<Test>::get(0);
+        assert!(pokedex.has_seen(0));
+        assert!(!pokedex.has_caught(0));
+        assert_eq!(pokedex.seen_count(), 1);
+        assert!(OmniPallet::Pokedexes::<Test>::get(1).has_seen(2));
+    });
+}
+
+#[test]
+fn pokedex_completion_per_region() {
+    new_test_ext().execute_with(|| {
+        let mut region = get_test_region::<Test>();
+        region.id = 1;
+        region.population_demographics.remove(&2);
+        OmniPallet::RegionIdToRegion::<Test>::insert(1, region);
+
+        OmniModule::record_caught(&0, 0);
+        assert_eq!(
+            OmniModule::pokedex_completion(&0),
+            vec![
+                (0, Permill::from_rational(1u32, 3)),
+                (1, Permill::from_percent(50))
+            ]
+        );
+        OmniModule::record_caught(&0, 1);
+        assert_eq!(OmniModule::pokedex_completion(&0)[1], (1, Permill::one()));
+    });
+}
+
+// ---- Quests ----
+
+fn catch_quest(
+    species_id: Option<JokeymonSpeciesId>,
+    diet: Option<Diet>,
+    count: u32,
+) -> QuestObjective {
+    QuestObjective::Catch {
+        species_id,
+        diet,
+        region_id: Some(0),
+        count,
+    }
+}
+
+#[test]
+fn quests_are_managed_by_admin() {
+    new_test_ext().execute_with(|| {
+        let objective = catch_quest(None, None, 1);
+        assert_noop!(
+            OmniModule::create_quest(
+                RuntimeOrigin::signed(0),
+                objective.clone(),
+                QuestReward::Tokens(10)
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            OmniModule::create_quest(
+                RuntimeOrigin::root(),
+                catch_quest(None, None, 0),
+                QuestReward::Tokens(10)
+            ),
+            Error::<Test>::InvalidQuest
+        );
+
+        for _ in 0..MaxQuests::get() {
+            assert_ok!(OmniModule::create_quest(
+                RuntimeOrigin::root(),
+                objective.clone(),
+                QuestReward::Tokens(10)
+            ));
+        }
+        System::assert_last_event(Event::QuestCreated { quest_id: 3 }.into());
+        assert_noop!(
+            OmniModule::create_quest(
+                RuntimeOrigin::root(),
+                objective.clone(),
+                QuestReward::Tokens(10)
+            ),
+            Error::<Test>::TooManyQuests
+        );
+
+        assert_ok!(OmniModule::remove_quest(RuntimeOrigin::root(), 1));
+        assert_eq!(
+            OmniPallet::OpenQuests::<Test>::get().into_inner(),
+            vec![0, 2, 3]
+        );
+        assert_noop!(
+            OmniModule::remove_quest(RuntimeOrigin::root(), 1),
+            Error::<Test>::UnknownQuest
+        );
+        // ids are not reused
+        assert_ok!(OmniModule::create_quest(
+            RuntimeOrigin::root(),
+            objective,
+            QuestReward::Tokens(10)
+        ));
+        System::assert_last_event(Event::QuestCreated { quest_id: 4 }.into());
+    });
+}
+
+#[test]
+fn catching_progresses_matching_quests() {
+    new_test_ext().execute_with(|| {
+        // the mock always catches species 0, a herbivore
+        let quests = [
+            catch_quest(None, Some(Diet::Herbivore), 2),
+            catch_quest(Some(0), None, 5),
+            catch_quest(None, Some(Diet::Carnivore), 1),
+            QuestObjective::Catch {
+                species_id: None,
+                diet: None,
+                region_id: Some(1),
+                count: 1,
+            },
+        ];
+        for objective in quests {
+            assert_ok!(OmniModule::create_quest(
+                RuntimeOrigin::root(),
+                objective,
+                QuestReward::Tokens(10)
+            ));
+        }
+
+        for _ in 0..3 {
+            assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0)));
+        }
+        let progress = |quest_id| OmniPallet::QuestProgresses::<Test>::get(0u64, quest_id).count;
+        // progress stops at the objective
+        assert_eq!(progress(0), 2);
+        assert_eq!(progress(1), 3);
+        assert_eq!(progress(2), 0);
+        assert_eq!(progress(3), 0);
+        System::assert_has_event(
+            Event::QuestCompleted {
+                who: 0,
+                quest_id: 0,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn claim_quest_reward_pays_tokens_once() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::mint_into(&OmniModule::account_id(), 1_000));
+        assert_ok!(OmniModule::create_quest(
+            RuntimeOrigin::root(),
+            catch_quest(None, None, 1),
+            QuestReward::Tokens(100)
+        ));
+        assert_noop!(
+            OmniModule::claim_quest_reward(RuntimeOrigin::signed(0), 0),
+            Error::<Test>::QuestNotComplete
+        );
+        assert_noop!(
+            OmniModule::claim_quest_reward(RuntimeOrigin::signed(0), 1),
+            Error::<Test>::UnknownQuest
+        );
+
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0)));
+        let before = Balances::free_balance(0u64);
+        assert_ok!(OmniModule::claim_quest_reward(RuntimeOrigin::signed(0), 0));
+        assert_eq!(Balances::free_balance(0u64), before + 100);
+        System::assert_last_event(
+            Event::QuestRewardClaimed {
+                who: 0,
+                quest_id: 0,
+                reward: QuestReward::Tokens(100),
+            }
+            .into(),
+        );
+        assert_noop!(
+            OmniModule::claim_quest_reward(RuntimeOrigin::signed(0), 0),
+            Error::<Test>::QuestAlreadyClaimed
+        );
+    });
+}
+
+#[test]
+fn claim_quest_reward_mints_jokeymon() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OmniModule::create_quest(
+            RuntimeOrigin::root(),
+            catch_quest(None, None, 1),
+            QuestReward::Jokeymon(2)
+        ));
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0)));
+        assert_ok!(OmniModule::claim_quest_reward(RuntimeOrigin::signed(0), 0));
+
+        let owned = OmniPallet::AccountToData::<Test>::get(0).jokeymon;
+        assert_eq!(owned.len(), 2);
+        assert_eq!(
+            OmniPallet::JokeymonIdToData::<Test>::get(owned[1])
+                .unwrap()
+                .id,
+            2
+        );
+        assert_eq!(
+            OmniPallet::JokeymonIdToOwner::<Test>::get(owned[1]),
+            Some(0)
+        );
+        assert_eq!(
+            Balances::balance_on_hold(&HoldReason::JokeymonDeposit.into(), &0),
+            2 * JokeymonDeposit::get()
+        );
+        assert!(System::events().iter().any(|record| matches!(
+            record.event,
+            RuntimeEvent::OmniModule(Event::JokeymonRewarded {
+                species_id: 2,
+                who: 0,
+                ..
+            })
+        )));
+    });
+}
+
+// ---- Gyms ----
+
+fn setup_gym() {
+    assert_ok!(OmniModule::create_gym(RuntimeOrigin::root(), 0));
+}
+
+#[test]
+fn gyms_are_created_by_admin() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            OmniModule::create_gym(RuntimeOrigin::signed(0), 0),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            OmniModule::create_gym(RuntimeOrigin::root(), 7),
+            Error::<Test>::UnknownRegion
+        );
+        setup_gym();
+        assert_noop!(
+            OmniModule::create_gym(RuntimeOrigin::root(), 0),
+            Error::<Test>::GymAlreadyExists
+        );
+        assert_eq!(OmniPallet::Gyms::<Test>::get(0), Some(Gym::default()));
+    });
+}
+
+#[test]
+fn empty_gym_is_captured_without_a_fight() {
+    new_test_ext().execute_with(|| {
+        let strong = give_jokeymon(0, 2);
+        assert_noop!(
+            OmniModule::challenge_gym(RuntimeOrigin::signed(0), team(&[strong])),
+            Error::<Test>::GymNotFound
+        );
+        setup_gym();
+
+        assert_ok!(OmniModule::challenge_gym(
+            RuntimeOrigin::signed(0),
+            team(&[strong])
+        ));
+        let gym = OmniPallet::Gyms::<Test>::get(0).unwrap();
+        assert_eq!(gym.controller, Some(0));
+        assert_eq!(gym.team.into_inner(), vec![strong]);
+        assert!(OmniPallet::LockedJokeymon::<Test>::contains_key(strong));
+        System::assert_last_event(
+            Event::GymCaptured {
+                region_id: 0,
+                who: 0,
+                previous: None,
+            }
+            .into(),
+        );
+
+        assert_noop!(
+            OmniModule::challenge_gym(RuntimeOrigin::signed(0), team(&[strong])),
+            Error::<Test>::AlreadyGymController
+        );
+        assert_noop!(
+            OmniModule::release_jokeymon(RuntimeOrigin::signed(0), strong),
+            Error::<Test>::JokeymonLocked
+        );
+    });
+}
+
+#[test]
+fn gym_changes_hands_only_when_defender_loses() {
+    new_test_ext().execute_with(|| {
+        setup_gym();
+        let weak = give_jokeymon(0, 0);
+        let strong = give_jokeymon(1, 2);
+        let strong_too = give_jokeymon(2, 2);
+        assert_ok!(OmniModule::challenge_gym(
+            RuntimeOrigin::signed(0),
+            team(&[weak])
+        ));
+
+        // challenging from another region
+        OmniPallet::AccountToData::<Test>::mutate(1, |data| data.current_region = 1);
+        assert_noop!(
+            OmniModule::challenge_gym(RuntimeOrigin::signed(1), team(&[strong])),
+            Error::<Test>::GymNotFound
+        );
+        OmniPallet::AccountToData::<Test>::mutate(1, |data| data.current_region = 0);
+
+        assert_ok!(OmniModule::challenge_gym(
+            RuntimeOrigin::signed(1),
+            team(&[strong])
+        ));
+        let gym = OmniPallet::Gyms::<Test>::get(0).unwrap();
+        assert_eq!(gym.controller, Some(1));
+        assert!(!OmniPallet::LockedJokeymon::<Test>::contains_key(weak));
+        assert!(OmniPallet::LockedJokeymon::<Test>::contains_key(strong));
+        System::assert_last_event(
+            Event::GymCaptured {
+                region_id: 0,
+                who: 1,
+                previous: Some(0),
+            }
+            .into(),
+        );
+
+        // with the mock randomness an even match is a draw, which keeps the controller
+        assert_ok!(OmniModule::challenge_gym(
+            RuntimeOrigin::signed(2),
+            team(&[strong_too])
+        ));
+        assert_eq!(
+            OmniPallet::Gyms::<Test>::get(0).unwrap().controller,
+            Some(1)
+        );
+        assert!(!OmniPallet::LockedJokeymon::<Test>::contains_key(
+            strong_too
+        ));
+        System::assert_last_event(
+            Event::GymDefended {
+                region_id: 0,
+                controller: 1,
+                challenger: 2,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn gym_controller_earns_per_block() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::mint_into(&OmniModule::account_id(), 1_000));
+        setup_gym();
+        let strong = give_jokeymon(0, 2);
+        assert_ok!(OmniModule::challenge_gym(
+            RuntimeOrigin::signed(0),
+            team(&[strong])
+        ));
+
+        System::set_block_number(11);
+        assert_noop!(
+            OmniModule::claim_gym_reward(RuntimeOrigin::signed(1), 0),
+            Error::<Test>::NotGymController
+        );
+        assert_ok!(OmniModule::claim_gym_reward(RuntimeOrigin::signed(0), 0));
+        assert_eq!(
+            Balances::free_balance(0u64),
+            INITIAL_BALANCE + 10 * GymReward::get()
+        );
+        System::assert_last_event(
+            Event::GymRewardPaid {
+                region_id: 0,
+                who: 0,
+                amount: 10 * GymReward::get(),
+            }
+            .into(),
+        );
+
+        // leaving pays what was earned since and frees the team
+        System::set_block_number(15);
+        assert_ok!(OmniModule::abandon_gym(RuntimeOrigin::signed(0), 0));
+        assert_eq!(
+            Balances::free_balance(0u64),
+            INITIAL_BALANCE + 14 * GymReward::get()
+        );
+        assert!(!OmniPallet::LockedJokeymon::<Test>::contains_key(strong));
+        assert_eq!(OmniPallet::Gyms::<Test>::get(0).unwrap().controller, None);
+    });
+}
+
+#[test]
+fn gym_reward_is_capped_by_the_pot() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::mint_into(&OmniModule::account_id(), 20));
+        setup_gym();
+        let strong = give_jokeymon(0, 2);
+        assert_ok!(OmniModule::challenge_gym(
+            RuntimeOrigin::signed(0),
+            team(&[strong])
+        ));
+
+        System::set_block_number(101);
+        assert_ok!(OmniModule::remove_gym(RuntimeOrigin::root(), 0));
+        // the pot keeps its existential deposit
+        assert_eq!(Balances::free_balance(0u64), INITIAL_BALANCE + 19);
+        assert!(OmniPallet::Gyms::<Test>::get(0).is_none());
+        assert!(!OmniPallet::LockedJokeymon::<Test>::contains_key(strong));
+    });
+}
+
+// ---- Guilds ----
+
+/// Guild 0 led by account 0 with account 1 as a member
+fn setup_guild() {
+    assert_ok!(OmniModule::create_guild(RuntimeOrigin::signed(0)));
+    assert_ok!(OmniModule::join_guild(RuntimeOrigin::signed(1), 0));
+}
+
+fn role(who: u64) -> Option<GuildRole> {
+    OmniPallet::GuildMembers::<Test>::get(who).map(|m| m.role)
+}
+
+#[test]
+fn guild_membership_works() {
+    new_test_ext().execute_with(|| {
+        setup_guild();
+        assert_eq!(role(0), Some(GuildRole::Leader));
+        assert_eq!(role(1), Some(GuildRole::Member));
+        assert_noop!(
+            OmniModule::join_guild(RuntimeOrigin::signed(1), 0),
+            Error::<Test>::AlreadyInGuild
+        );
+        assert_noop!(
+            OmniModule::join_guild(RuntimeOrigin::signed(2), 1),
+            Error::<Test>::UnknownGuild
+        );
+        assert_ok!(OmniModule::join_guild(RuntimeOrigin::signed(2), 0));
+        assert_noop!(
+            OmniModule::join_guild(RuntimeOrigin::signed(3), 0),
+            Error::<Test>::GuildFull
+        );
+        assert_eq!(OmniPallet::Guilds::<Test>::get(0).unwrap().members, 3);
+
+        assert_noop!(
+            OmniModule::leave_guild(RuntimeOrigin::signed(0)),
+            Error::<Test>::LeaderCannotLeave
+        );
+        assert_ok!(OmniModule::leave_guild(RuntimeOrigin::signed(2)));
+        System::assert_last_event(
+            Event::GuildLeft {
+                guild_id: 0,
+                who: 2,
+            }
+            .into(),
+        );
+        assert_eq!(OmniPallet::Guilds::<Test>::get(0).unwrap().members, 2);
+        assert_eq!(role(2), None);
+    });
+}
+
+#[test]
+fn guild_roles_gate_actions() {
+    new_test_ext().execute_with(|| {
+        setup_guild();
+        assert_ok!(OmniModule::join_guild(RuntimeOrigin::signed(2), 0));
+
+        assert_noop!(
+            OmniModule::set_guild_role(RuntimeOrigin::signed(1), 2, GuildRole::Officer),
+            Error::<Test>::InsufficientGuildRole
+        );
+        assert_noop!(
+            OmniModule::kick_from_guild(RuntimeOrigin::signed(1), 2),
+            Error::<Test>::InsufficientGuildRole
+        );
+        assert_ok!(OmniModule::set_guild_role(
+            RuntimeOrigin::signed(0),
+            1,
+            GuildRole::Officer
+        ));
+
+        // officers can only remove members below them
+        assert_noop!(
+            OmniModule::kick_from_guild(RuntimeOrigin::signed(1), 0),
+            Error::<Test>::InsufficientGuildRole
+        );
+        assert_ok!(OmniModule::kick_from_guild(RuntimeOrigin::signed(1), 2));
+        assert_eq!(role(2), None);
+
+        // handing over leadership demotes the old leader
+        assert_ok!(OmniModule::set_guild_role(
+            RuntimeOrigin::signed(0),
+            1,
+            GuildRole::Leader
+        ));
+        assert_eq!(role(0), Some(GuildRole::Officer));
+        assert_eq!(role(1), Some(GuildRole::Leader));
+        assert_ok!(OmniModule::leave_guild(RuntimeOrigin::signed(0)));
+    });
+}
+
+#[test]
+fn guild_pot_is_funded_by_members_and_spent_by_leader() {
+    new_test_ext().execute_with(|| {
+        setup_guild();
+        let pot = OmniModule::guild_account_id(0);
+        assert_ok!(OmniModule::fund_guild(RuntimeOrigin::signed(1), 100));
+        assert_eq!(Balances::free_balance(pot), 100);
+        assert_noop!(
+            OmniModule::fund_guild(RuntimeOrigin::signed(2), 100),
+            Error::<Test>::NotInGuild
+        );
+
+        assert_noop!(
+            OmniModule::withdraw_guild_funds(RuntimeOrigin::signed(1), 1, 50),
+            Error::<Test>::InsufficientGuildRole
+        );
+        assert_ok!(OmniModule::withdraw_guild_funds(
+            RuntimeOrigin::signed(0),
+            2,
+            50
+        ));
+        assert_eq!(Balances::free_balance(2u64), INITIAL_BALANCE + 50);
+
+        // the last member out takes what's left
+        assert_ok!(OmniModule::leave_guild(RuntimeOrigin::signed(1)));
+        assert_ok!(OmniModule::leave_guild(RuntimeOrigin::signed(0)));
+        assert_eq!(Balances::free_balance(0u64), INITIAL_BALANCE + 50);
+        assert_eq!(Balances::free_balance(pot), 0);
+        assert!(OmniPallet::Guilds::<Test>::get(0).is_none());
+        System::assert_last_event(Event::GuildDissolved { guild_id: 0 }.into());
+    });
+}
+
+#[test]
+fn guild_counts_member_catches() {
+    new_test_ext().execute_with(|| {
+        setup_guild();
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0)));
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(1)));
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(2)));
+        assert_eq!(OmniPallet::Guilds::<Test>::get(0).unwrap().catches, 2);
+    });
+}
+
+#[test]
+fn guild_roster_can_be_borrowed_for_battle() {
+    new_test_ext().execute_with(|| {
+        setup_guild();
+        let pot = OmniModule::guild_account_id(0);
+        assert_ok!(OmniModule::fund_guild(RuntimeOrigin::signed(0), 100));
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0)));
+        let jokeymon_id = OmniPallet::AccountToData::<Test>::get(0).jokeymon[0];
+
+        assert_ok!(OmniModule::deposit_to_roster(
+            RuntimeOrigin::signed(0),
+            jokeymon_id
+        ));
+        assert_eq!(
+            OmniPallet::JokeymonIdToOwner::<Test>::get(jokeymon_id),
+            Some(pot)
+        );
+        assert_eq!(
+            Balances::balance_on_hold(&HoldReason::JokeymonDeposit.into(), &pot),
+            JokeymonDeposit::get()
+        );
+
+        assert_ok!(OmniModule::borrow_from_roster(
+            RuntimeOrigin::signed(1),
+            jokeymon_id
+        ));
+        assert_noop!(
+            OmniModule::borrow_from_roster(RuntimeOrigin::signed(0), jokeymon_id),
+            Error::<Test>::JokeymonBorrowed
+        );
+        assert_noop!(
+            OmniModule::withdraw_from_roster(RuntimeOrigin::signed(0), jokeymon_id),
+            Error::<Test>::JokeymonBorrowed
+        );
+        assert_noop!(
+            OmniModule::leave_guild(RuntimeOrigin::signed(1)),
+            Error::<Test>::HasBorrowedJokeymon
+        );
+
+        // the borrower can fight with it but not return it while it's locked in a challenge
+        assert_ok!(OmniModule::challenge(
+            RuntimeOrigin::signed(1),
+            5,
+            team(&[jokeymon_id]),
+            0
+        ));
+        assert_noop!(
+            OmniModule::return_to_roster(RuntimeOrigin::signed(1), jokeymon_id),
+            Error::<Test>::JokeymonLocked
+        );
+        assert_ok!(OmniModule::cancel_challenge(RuntimeOrigin::signed(1), 5));
+
+        assert_noop!(
+            OmniModule::return_to_roster(RuntimeOrigin::signed(2), jokeymon_id),
+            Error::<Test>::NotInGuild
+        );
+        // the leader recalls it
+        assert_ok!(OmniModule::return_to_roster(
+            RuntimeOrigin::signed(0),
+            jokeymon_id
+        ));
+        assert_eq!(
+            OmniPallet::GuildMembers::<Test>::get(1).unwrap().borrowed,
+            0
+        );
+
+        assert_noop!(
+            OmniModule::withdraw_from_roster(RuntimeOrigin::signed(1), jokeymon_id),
+            Error::<Test>::InsufficientGuildRole
+        );
+        assert_ok!(OmniModule::withdraw_from_roster(
+            RuntimeOrigin::signed(0),
+            jokeymon_id
+        ));
+        assert_eq!(
+            OmniPallet::JokeymonIdToOwner::<Test>::get(jokeymon_id),
+            Some(0)
+        );
+        assert_noop!(
+            OmniModule::borrow_from_roster(RuntimeOrigin::signed(1), jokeymon_id),
+            Error::<Test>::NotInRoster
+        );
+    });
+}
+
+// ---- World Administration ----
+
+fn demographics(
+    populations: &[(JokeymonSpeciesId, JokeymonCount)],
+) -> RegionPopulationDemographics<Test> {
+    populations
+        .iter()
+        .copied()
+        .collect::<std::collections::BTreeMap<_, _>>()
+        .try_into()
+        .unwrap()
+}
+
+#[test]
+fn create_region_takes_the_next_free_id() {
+    new_test_ext().execute_with(|| {
+        let populations = demographics(&[(0, 40), (2, 10)]);
+        assert_noop!(
+            OmniModule::create_region(RuntimeOrigin::signed(0), 1, 2, 500, populations.clone()),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            OmniModule::create_region(RuntimeOrigin::root(), 1, 2, 500, demographics(&[(9, 1)])),
+            Error::<Test>::UnknownSpecies
+        );
+        assert_noop!(
+            OmniModule::create_region(
+                RuntimeOrigin::root(),
+                1,
+                2,
+                500,
+                demographics(&[(0, MAX_SPECIES_POPULATION + 1)])
+            ),
+            Error::<Test>::PopulationTooLarge
+        );
+
+        // the mock's region 0 was not counted by the nonce
+        assert_ok!(OmniModule::create_region(
+            RuntimeOrigin::root(),
+            1,
+            2,
+            500,
+            populations.clone()
+        ));
+        let region = OmniPallet::RegionIdToRegion::<Test>::get(1);
+        assert_eq!(region.id, 1);
+        assert_eq!(region.total_population, 50);
+        assert_eq!(region.energy_yield, 500);
+        assert_eq!(OmniPallet::RegionNonce::<Test>::get(), 2);
+        System::assert_last_event(
+            Event::RegionCreated {
+                region_id: 1,
+                latitude: 1,
+                longitude: 2,
+                energy_yield: 500,
+                population_demographics: populations,
+            }
+            .into(),
+        );
+        assert_ok!(OmniModule::travel(RuntimeOrigin::signed(0), 1));
+    });
+}
+
+#[test]
+fn set_species_rejects_unknown_targets_and_cycles() {
+    new_test_ext().execute_with(|| {
+        let species = |id, evolves_to| JokeymonSpeciesData {
+            id,
+            avg_weight: 10,
+            avg_daily_food_consumption: 10,
+            diet: Diet::Herbivore,
+            evolves_to,
+        };
+        assert_noop!(
+            OmniModule::set_species(RuntimeOrigin::signed(0), species(3, None)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            OmniModule::set_species(RuntimeOrigin::root(), species(3, Some(9))),
+            Error::<Test>::UnknownSpecies
+        );
+        // 0 evolves to 1 and 1 to 2
+        assert_noop!(
+            OmniModule::set_species(RuntimeOrigin::root(), species(2, Some(0))),
+            Error::<Test>::EvolutionCycle
+        );
+        assert_noop!(
+            OmniModule::set_species(RuntimeOrigin::root(), species(3, Some(3))),
+            Error::<Test>::EvolutionCycle
+        );
+        assert_noop!(
+            OmniModule::set_species(
+                RuntimeOrigin::root(),
+                JokeymonSpeciesData {
+                    avg_daily_food_consumption: 0,
+                    ..species(3, None)
+                }
+            ),
+            Error::<Test>::NoFoodConsumption
+        );
+
+        assert_ok!(OmniModule::set_species(
+            RuntimeOrigin::root(),
+            species(3, Some(0))
+        ));
+        assert_eq!(
+            OmniPallet::SpeciesIdToSpeciesData::<Test>::get(3),
+            species(3, Some(0))
+        );
+        System::assert_last_event(
+            Event::SpeciesSet {
+                species: species(3, Some(0)),
+            }
+            .into(),
+        );
+    });
+}
+
+// ---- Migrations ----
+
+/// Put storage back to version 1, where accounts had no party
+fn setup_v1_state(per_account: u32) -> Vec<(u64, JokeymonId)> {
+    let mut owned = Vec::new();
+    for who in 0..5u64 {
+        for species_id in 0..per_account {
+            owned.push((who, give_jokeymon(who, species_id % 3)));
+        }
+        let data = OmniPallet::AccountToData::<Test>::take(who);
+        v2::v1::AccountToData::<Test>::insert(
+            who,
+            v2::v1::AccountData {
+                current_region: data.current_region,
+                jokeymon: data.jokeymon,
+            },
+        );
+    }
+    StorageVersion::new(1).put::<OmniModule>();
+    owned
+}
+
+/// Put storage back to version 0, where jokeymon had no owner entry either
+fn setup_v0_state() -> Vec<(u64, JokeymonId)> {
+    let owned = setup_v1_state(3);
+    let _ = OmniPallet::JokeymonIdToOwner::<Test>::clear(u32::MAX, None);
+    StorageVersion::new(0).put::<OmniModule>();
+    owned
+}
+
+/// Run a stepped migration to the end in a single step
+fn run_migration<M: SteppedMigration>() {
+    assert!(M::step(None, &mut WeightMeter::new()).unwrap().is_none());
+}
+
+#[test]
+fn migrate_v0_to_v1_fills_in_owners() {
+    new_test_ext().execute_with(|| {
+        let owned = setup_v0_state();
+        let region = OmniPallet::RegionIdToRegion::<Test>::get(0);
+
+        run_migration::<v1::MigrateV0ToV1<Test>>();
+
+        assert_eq!(
+            OmniModule::on_chain_storage_version(),
+            StorageVersion::new(1)
+        );
+        for (who, jokeymon_id) in owned {
+            assert_eq!(
+                OmniPallet::JokeymonIdToOwner::<Test>::get(jokeymon_id),
+                Some(who)
+            );
+        }
+        assert_eq!(OmniPallet::RegionIdToRegion::<Test>::get(0), region);
+
+        // running it again does nothing
+        let _ = OmniPallet::JokeymonIdToOwner::<Test>::clear(u32::MAX, None);
+        run_migration::<v1::MigrateV0ToV1<Test>>();
+        assert_eq!(OmniPallet::JokeymonIdToOwner::<Test>::iter().count(), 0);
+    });
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn migrate_v0_to_v1_passes_upgrade_checks() {
+    new_test_ext().execute_with(|| {
+        setup_v0_state();
+        let state = v1::MigrateV0ToV1::<Test>::pre_upgrade().unwrap();
+        run_migration::<v1::MigrateV0ToV1<Test>>();
+        assert_ok!(v1::MigrateV0ToV1::<Test>::post_upgrade(state));
+    });
+}
+
+#[test]
+fn migrate_v0_to_v1_runs_over_several_steps() {
+    new_test_ext().execute_with(|| {
+        let owned = setup_v0_state();
+        let per_account = <Test as frame_system::Config>::DbWeight::get()
+            .reads_writes(1, MaxJokeymonHoldable::get().into());
+
+        let mut cursor = None;
+        let mut steps = 0;
+        loop {
+            // room for two accounts per step
+            let mut meter = WeightMeter::with_limit(per_account * 2);
+            cursor = v1::MigrateV0ToV1::<Test>::step(cursor, &mut meter).unwrap();
+            steps += 1;
+            if cursor.is_none() {
+                break;
+            }
+            assert_eq!(
+                OmniModule::on_chain_storage_version(),
+                StorageVersion::new(0)
+            );
+        }
+
+        // 5 accounts, two per step, and a final step finding no more accounts
+        assert_eq!(steps, 3);
+        assert_eq!(
+            OmniModule::on_chain_storage_version(),
+            StorageVersion::new(1)
+        );
+        for (who, jokeymon_id) in owned {
+            assert_eq!(
+                OmniPallet::JokeymonIdToOwner::<Test>::get(jokeymon_id),
+                Some(who)
+            );
+        }
+
+        // too little weight to migrate a single account
+        StorageVersion::new(0).put::<OmniModule>();
+        let mut meter = WeightMeter::with_limit(Weight::zero());
+        assert!(v1::MigrateV0ToV1::<Test>::step(None, &mut meter).is_err());
+    });
+}
+
+#[test]
+fn migrate_v1_to_v2_fills_in_parties() {
+    new_test_ext().execute_with(|| {
+        let owned = setup_v1_state(8);
+
+        run_migration::<v2::MigrateV1ToV2<Test>>();
+
+        assert_eq!(
+            OmniModule::on_chain_storage_version(),
+            StorageVersion::new(2)
+        );
+        for who in 0..5u64 {
+            let held: Vec<_> = owned
+                .iter()
+                .filter(|(owner, _)| *owner == who)
+                .map(|(_, jokeymon_id)| *jokeymon_id)
+                .collect();
+            let data = OmniPallet::AccountToData::<Test>::get(who);
+            assert_eq!(data.jokeymon.to_vec(), held);
+            assert_eq!(data.party.to_vec(), held[..6]);
+        }
+        assert_ok!(OmniModule::do_try_state());
+    });
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn migrate_v1_to_v2_passes_upgrade_checks() {
+    new_test_ext().execute_with(|| {
+        setup_v1_state(8);
+        let state = v2::MigrateV1ToV2::<Test>::pre_upgrade().unwrap();
+        run_migration::<v2::MigrateV1ToV2<Test>>();
+        assert_ok!(v2::MigrateV1ToV2::<Test>::post_upgrade(state));
+    });
+}
+
+#[test]
+fn migrate_v1_to_v2_runs_over_several_steps() {
+    new_test_ext().execute_with(|| {
+        setup_v1_state(2);
+        let per_account = <Test as frame_system::Config>::DbWeight::get().reads_writes(1, 1);
+
+        let mut cursor = None;
+        let mut steps = 0;
+        loop {
+            let mut meter = WeightMeter::with_limit(per_account * 2);
+            cursor = v2::MigrateV1ToV2::<Test>::step(cursor, &mut meter).unwrap();
+            steps += 1;
+            if cursor.is_none() {
+                break;
+            }
+            assert_eq!(
+                OmniModule::on_chain_storage_version(),
+                StorageVersion::new(1)
+            );
+        }
+
+        assert_eq!(steps, 3);
+        assert_eq!(
+            OmniModule::on_chain_storage_version(),
+            StorageVersion::new(2)
+        );
+        for who in 0..5u64 {
+            let data = OmniPallet::AccountToData::<Test>::get(who);
+            assert_eq!(data.party, data.jokeymon.to_vec());
+        }
+        assert_ok!(OmniModule::do_try_state());
+    });
+}
+
+#[test]
+fn migrations_have_distinct_ids() {
+    assert_ne!(
+        v1::MigrateV0ToV1::<Test>::id().encode(),
+        v2::MigrateV1ToV2::<Test>::id().encode()
+    );
+    assert_eq!(
+        &v1::MigrateV0ToV1::<Test>::id().pallet_id,
+        b"pallet-omni-mbm-v1"
+    );
+    assert_eq!(
+        &v2::MigrateV1ToV2::<Test>::id().pallet_id,
+        b"pallet-omni-mbm-v2"
+    );
+}
+
+// ---- Invariants ----
+
+#[test]
+fn try_state_holds_through_gameplay() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OmniModule::do_try_state());
+        for who in 0..3 {
+            assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(who)));
+        }
+        let jokeymon_id = OmniPallet::AccountToData::<Test>::get(0).jokeymon[0];
+        assert_ok!(OmniModule::transfer_jokeymon(
+            RuntimeOrigin::signed(0),
+            1,
+            jokeymon_id
+        ));
+        assert_ok!(OmniModule::release_jokeymon(
+            RuntimeOrigin::signed(1),
+            jokeymon_id
+        ));
+        for n in 2..5 {
+            OmniModule::on_initialize(n);
+        }
+        assert_ok!(OmniModule::do_try_state());
+    });
+}
+
+#[test]
+fn try_state_catches_population_mismatch() {
+    new_test_ext().execute_with(|| {
+        OmniPallet::RegionIdToRegion::<Test>::mutate(0, |region| region.total_population += 1);
+        assert!(OmniModule::do_try_state().is_err());
+    });
+}
+
+#[test]
+fn try_state_catches_missing_jokeymon_data() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = give_jokeymon(0, 0);
+        OmniPallet::JokeymonIdToData::<Test>::remove(jokeymon_id);
+        assert!(OmniModule::do_try_state().is_err());
+    });
+}
+
+#[test]
+fn try_state_catches_double_ownership() {
+    new_test_ext().execute_with(|| {
+        let jokeymon_id = give_jokeymon(0, 0);
+        assert_ok!(OmniModule::do_try_state());
+        OmniPallet::AccountToData::<Test>::mutate(1, |data| {
+            data.jokeymon.try_push(jokeymon_id).unwrap()
+        });
+        assert!(OmniModule::do_try_state().is_err());
+    });
+}
+
+#[test]
+fn try_state_catches_stale_id_nonce() {
+    new_test_ext().execute_with(|| {
+        give_jokeymon(0, 0);
+        assert_ok!(OmniModule::do_try_state());
+        OmniPallet::JokeymonIdNonce::<Test>::put(0);
+        assert!(OmniModule::do_try_state().is_err());
+    });
+}
+
+// ---- Party ----
+
+#[test]
+fn caught_jokeymon_fill_the_party_then_the_box() {
+    new_test_ext().execute_with(|| {
+        let ids: Vec<_> = (0..8).map(|_| give_jokeymon(0, 0)).collect();
+        let data = OmniPallet::AccountToData::<Test>::get(0);
+        assert_eq!(data.party.to_vec(), ids[..6]);
+        assert_eq!(data.jokeymon.to_vec(), ids);
+
+        assert_ok!(OmniModule::catch_jokeymon(RuntimeOrigin::signed(0)));
+        let data = OmniPallet::AccountToData::<Test>::get(0);
+        assert_eq!(data.party.len(), 6);
+        assert_eq!(data.jokeymon.len(), 9);
+    });
+}
+
+#[test]
+fn set_party_checks_ownership_and_duplicates() {
+    new_test_ext().execute_with(|| {
+        let a = give_jokeymon(0, 0);
+        let b = give_jokeymon(0, 1);
+        let other = give_jokeymon(1, 0);
+
+        assert_noop!(
+            OmniModule::set_party(RuntimeOrigin::signed(0), vec![a, a].try_into().unwrap()),
+            Error::<Test>::InvalidTeam
+        );
+        assert_noop!(
+            OmniModule::set_party(RuntimeOrigin::signed(0), vec![a, other].try_into().unwrap()),
+            Error::<Test>::NotJokeymonOwner
+        );
+
+        let party: Team<Test> = vec![b, a].try_into().unwrap();
+        assert_ok!(OmniModule::set_party(
+            RuntimeOrigin::signed(0),
+            party.clone()
+        ));
+        assert_eq!(OmniPallet::AccountToData::<Test>::get(0).party, party);
+        System::assert_last_event(Event::PartySet { who: 0, party }.into());
+
+        assert_ok!(OmniModule::set_party(
+            RuntimeOrigin::signed(0),
+            Default::default()
+        ));
+        assert!(OmniPallet::AccountToData::<Test>::get(0).party.is_empty());
+    });
+}
+
+#[test]
+fn jokeymon_move_between_party_and_box() {
+    new_test_ext().execute_with(|| {
+        let ids: Vec<_> = (0..7).map(|_| give_jokeymon(0, 0)).collect();
+        let boxed = ids[6];
+
+        assert_noop!(
+            OmniModule::deposit_to_box(RuntimeOrigin::signed(0), boxed),
+            Error::<Test>::NotInParty
+        );
+        assert_noop!(
+            OmniModule::withdraw_from_box(RuntimeOrigin::signed(0), boxed),
+            Error::<Test>::PartyFull
+        );
+        assert_noop!(
+            OmniModule::withdraw_from_box(RuntimeOrigin::signed(0), ids[0]),
+            Error::<Test>::AlreadyInParty
+        );
+        assert_noop!(
+            OmniModule::withdraw_from_box(RuntimeOrigin::signed(1), boxed),
+            Error::<Test>::NotJokeymonOwner
+        );
+
+        assert_ok!(OmniModule::deposit_to_box(RuntimeOrigin::signed(0), ids[0]));
+        assert_ok!(OmniModule::withdraw_from_box(
+            RuntimeOrigin::signed(0),
+            boxed
+        ));
+        let data = OmniPallet::AccountToData::<Test>::get(0);
+        assert_eq!(data.party.to_vec(), [&ids[1..6], &[boxed]].concat());
+        assert_eq!(data.jokeymon.to_vec(), ids);
+        assert_ok!(OmniModule::do_try_state());
+    });
+}
+
+#[test]
+fn locked_jokeymon_stay_where_they_are() {
+    new_test_ext().execute_with(|| {
+        let fighting = give_jokeymon(0, 0);
+        let benched = give_jokeymon(0, 1);
+        let listed = give_jokeymon(0, 2);
+        assert_ok!(OmniModule::deposit_to_box(RuntimeOrigin::signed(0), listed));
+        assert_ok!(OmniModule::challenge(
+            RuntimeOrigin::signed(0),
+            1,
+            team(&[fighting]),
+            0
+        ));
+        assert_ok!(OmniModule::list_for_sale(
+            RuntimeOrigin::signed(0),
+            listed,
+            100
+        ));
+
+        // a jokeymon in a challenge can't be boxed or dropped from the party
+        assert_noop!(
+            OmniModule::deposit_to_box(RuntimeOrigin::signed(0), fighting),
+            Error::<Test>::JokeymonLocked
+        );
+        assert_noop!(
+            OmniModule::set_party(RuntimeOrigin::signed(0), team(&[benched])),
+            Error::<Test>::JokeymonLocked
+        );
+        // and a listed jokeymon can't leave the box
+        assert_noop!(
+            OmniModule::withdraw_from_box(RuntimeOrigin::signed(0), listed),
+            Error::<Test>::JokeymonLocked
+        );
+        assert_noop!(
+            OmniModule::set_party(RuntimeOrigin::signed(0), team(&[fighting, benched, listed])),
+            Error::<Test>::JokeymonLocked
+        );
+
+        // unlocked jokeymon still move, and locked ones can be reordered
+        assert_ok!(OmniModule::set_party(
+            RuntimeOrigin::signed(0),
+            team(&[fighting])
+        ));
+        assert_ok!(OmniModule::withdraw_from_box(
+            RuntimeOrigin::signed(0),
+            benched
+        ));
+        assert_ok!(OmniModule::set_party(
+            RuntimeOrigin::signed(0),
+            team(&[benched, fighting])
+        ));
+    });
+}
+
+#[test]
+fn party_drops_jokeymon_that_leave_the_account() {
+    new_test_ext().execute_with(|| {
+        let traded = give_jokeymon(0, 0);
+        let kept = give_jokeymon(0, 1);
+
+        assert_ok!(OmniModule::transfer_jokeymon(
+            RuntimeOrigin::signed(0),
+            1,
+            traded
+        ));
+        assert_eq!(
+            OmniPallet::AccountToData::<Test>::get(0).party.to_vec(),
+            vec![kept]
+        );
+        assert!(OmniPallet::AccountToData::<Test>::get(1).party.is_empty());
+        assert_ok!(OmniModule::do_try_state());
+    });
+}
+
+#[test]
+fn rated_match_uses_the_party() {
+    new_test_ext().execute_with(|| {
+        give_jokeymon(0, 0);
+        let boxed = give_jokeymon(1, 0);
+        assert_ok!(OmniModule::deposit_to_box(RuntimeOrigin::signed(1), boxed));
+        join_ladder(&[0, 1]);
+
+        assert_noop!(
+            OmniModule::rated_match(RuntimeOrigin::signed(0), 1),
+            Error::<Test>::NoJokeymonToBattle
+        );
+    });
+}
+
+// ---- Genesis ----
+
+fn genesis_species(
+    id: JokeymonSpeciesId,
+    evolves_to: Option<JokeymonSpeciesId>,
+) -> (JokeymonSpeciesId, JokeymonSpeciesData) {
+    let data = JokeymonSpeciesData {
+        id,
+        avg_weight: 10,
+        avg_daily_food_consumption: 10,
+        diet: Diet::Herbivore,
+        evolves_to,
+    };
+    (id, data)
+}
+
+fn genesis_region(id: RegionId) -> (RegionId, Region<Test>) {
+    (
+        id,
+        Region {
+            id,
+            ..get_test_region::<Test>()
+        },
+    )
+}
+
+/// Build storage from an omni genesis config
+fn build_genesis(
+    region_id_to_region: Vec<(RegionId, Region<Test>)>,
+    species_id_to_data: Vec<(JokeymonSpeciesId, JokeymonSpeciesData)>,
+) -> sp_io::TestExternalities {
+    RuntimeGenesisConfig {
+        omni_module: OmniPallet::GenesisConfig {
+            region_id_to_region,
+            species_id_to_data,
+        },
+        ..Default::default()
+    }
+    .build_storage()
+    .unwrap()
+    .into()
+}
+
+fn valid_genesis_species() -> Vec<(JokeymonSpeciesId, JokeymonSpeciesData)> {
+    vec![
+        genesis_species(0, Some(1)),
+        genesis_species(1, Some(2)),
+        genesis_species(2, None),
+    ]
+}
+
+#[test]
+fn genesis_stores_world_and_sets_region_nonce() {
+    build_genesis(
+        vec![genesis_region(3), genesis_region(7)],
+        valid_genesis_species(),
+    )
+    .execute_with(|| {
+        assert_eq!(OmniPallet::RegionIdToRegion::<Test>::get(7).id, 7);
+        assert_eq!(
+            OmniPallet::SpeciesIdToSpeciesData::<Test>::get(1).evolves_to,
+            Some(2)
+        );
+        assert_eq!(OmniPallet::RegionNonce::<Test>::get(), 8);
+    });
+}
+
+#[test]
+#[should_panic(expected = "genesis region 1 is stored under id 2")]
+fn genesis_rejects_mismatched_region_id() {
+    let (_, region) = genesis_region(1);
+    build_genesis(vec![(2, region)], valid_genesis_species());
+}
+
+#[test]
+#[should_panic(expected = "genesis region 0 is defined twice")]
+fn genesis_rejects_duplicate_region() {
+    build_genesis(
+        vec![genesis_region(0), genesis_region(0)],
+        valid_genesis_species(),
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "genesis region 0 has a total population of 451 but its demographics add up to 450"
+)]
+fn genesis_rejects_inconsistent_total_population() {
+    let (id, mut region) = genesis_region(0);
+    region.total_population += 1;
+    build_genesis(vec![(id, region)], valid_genesis_species());
+}
+
+#[test]
+#[should_panic(expected = "genesis region 0 has unknown species 2")]
+fn genesis_rejects_unknown_species_in_region() {
+    build_genesis(
+        vec![genesis_region(0)],
+        vec![genesis_species(0, None), genesis_species(1, None)],
+    );
+}
+
+#[test]
+#[should_panic(expected = "genesis species 2 is stored under id 3")]
+fn genesis_rejects_mismatched_species_id() {
+    let mut species = valid_genesis_species();
+    species[2].0 = 3;
+    build_genesis(vec![], species);
+}
+
+#[test]
+#[should_panic(expected = "genesis species 1 evolves to unknown species 9")]
+fn genesis_rejects_unknown_evolution() {
+    build_genesis(
+        vec![],
+        vec![genesis_species(0, Some(1)), genesis_species(1, Some(9))],
+    );
+}
+
+#[test]
+#[should_panic(expected = "is part of an evolution cycle")]
+fn genesis_rejects_evolution_cycle() {
+    build_genesis(
+        vec![],
+        vec![
+            genesis_species(0, Some(1)),
+            genesis_species(1, Some(2)),
+            genesis_species(2, Some(1)),
+        ],
+    );
+}
+
+#[test]
+#[should_panic(expected = "genesis species 1 is a herbivore that eats nothing")]
+fn genesis_rejects_herbivores_without_food_intake() {
+    let mut species = valid_genesis_species();
+    species[1].1.avg_daily_food_consumption = 0;
+    build_genesis(vec![], species);
+}
+
+#[test]
+#[should_panic(expected = "genesis region 0 has 1000001 of species 0, at most 1000000 are allowed")]
+fn genesis_rejects_oversized_populations() {
+    let (id, mut region) = genesis_region(0);
+    region.population_demographics = demographics(&[(0, MAX_SPECIES_POPULATION + 1)]);
+    region.total_population = MAX_SPECIES_POPULATION as u64 + 1;
+    build_genesis(vec![(id, region)], valid_genesis_species());
+}
+
+// ---- World Generation ----
+
+fn world_params(seed: u64) -> WorldParams {
+    WorldParams {
+        seed,
+        width: 5,
+        height: 4,
+        herbivores: 6,
+        carnivores: 3,
+    }
+}
+
+#[test]
+fn generated_world_is_deterministic() {
+    let world = worldgen::generate::<Test>(&world_params(7));
+    let again = worldgen::generate::<Test>(&world_params(7));
+    assert_eq!(world.regions, again.regions);
+    assert_eq!(world.species, again.species);
+    assert_eq!(world.adjacency, again.adjacency);
+
+    let other = worldgen::generate::<Test>(&world_params(8));
+    assert_ne!(world.regions, other.regions);
+}
+
+#[test]
+fn generated_world_is_a_grid() {
+    let world = worldgen::generate::<Test>(&world_params(1));
+    assert_eq!(world.regions.len(), 20);
+    assert_eq!(world.species.len(), 9);
+
+    let region = &world.regions[7];
+    assert_eq!((region.latitude, region.longitude), (1, 2));
+    assert_eq!(world.adjacency[7], (7, vec![2, 6, 8, 12]));
+    assert_eq!(world.adjacency[0], (0, vec![1, 5]));
+    for (id, neighbours) in &world.adjacency {
+        for neighbour in neighbours {
+            assert!(world.adjacency[*neighbour as usize].1.contains(id));
+        }
+    }
+}
+
+#[test]
+fn generated_world_is_valid_genesis() {
+    let world = worldgen::generate::<Test>(&world_params(3));
+    let config = world.genesis_config();
+    build_genesis(config.region_id_to_region, config.species_id_to_data).execute_with(|| {
+        assert_eq!(OmniPallet::RegionNonce::<Test>::get(), 20);
+    });
+}
+
+#[test]
+fn generated_world_is_stable_under_population_updates() {
+    for seed in 0..10 {
+        let world = worldgen::generate::<Test>(&world_params(seed));
+        new_test_ext().execute_with(|| {
+            for species in &world.species {
+                OmniPallet::SpeciesIdToSpeciesData::<Test>::insert(species.id, species);
+            }
+            for region in &world.regions {
+                let mut updated = region.clone();
+                for _ in 0..100 {
+                    OmniModule::update_regional_population(
+                        &mut updated,
+                        &PopulationModel::default(),
+                    );
+                }
+                assert_eq!(&updated, region, "seed {seed} region {}", region.id);
+            }
+        });
+    }
+}
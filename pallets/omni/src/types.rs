@@ -1,9 +1,11 @@
 //! Some structs used in the omni pallet
 
 use codec::{Decode, Encode, MaxEncodedLen};
+use core::marker::PhantomData;
 use frame_support::{
-    BoundedBTreeMap, BoundedVec, DefaultNoBound, EqNoBound, OrdNoBound, PartialEqNoBound,
-    PartialOrdNoBound, RuntimeDebugNoBound,
+    traits::{fungible::Inspect, Get},
+    BoundedBTreeMap, BoundedVec, CloneNoBound, DefaultNoBound, EqNoBound, OrdNoBound,
+    PartialEqNoBound, PartialOrdNoBound, RuntimeDebugNoBound,
 };
 use frame_system::pallet_prelude::BlockNumberFor;
 use scale_info::TypeInfo;
@@ -17,6 +19,47 @@ pub type Coordinate = u32;
 pub type JokeymonSpeciesId = u32; // species identifier
 pub type JokeymonId = u64; // individual identifier
 pub type JokeymonCount = u32;
+/// Most jokeymon of one species a region can be given, keeps the population model in range
+pub const MAX_SPECIES_POPULATION: JokeymonCount = 1_000_000;
+
+// nonfungibles
+pub type JokeymonCollectionId = u32;
+/// The collection every jokeymon belongs to
+pub const JOKEYMON_COLLECTION: JokeymonCollectionId = 0;
+
+// currency
+pub type BalanceOf<T> =
+    <<T as crate::Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+
+// quests
+pub type QuestId = u32;
+
+// guilds
+pub type GuildId = u32;
+
+// pvp ladder
+pub type Rating = u32;
+pub type SeasonIndex = u32;
+
+// a team of jokeymon sent into battle
+pub type Team<T> = BoundedVec<JokeymonId, <T as crate::Config>::MaxTeamSize>;
+
+// the top rated accounts of a season, highest first
+pub type Standings<T> = BoundedVec<
+    (<T as frame_system::Config>::AccountId, Rating),
+    <T as crate::Config>::LeaderboardSize,
+>;
+
+// one bit per trackable species id
+pub type SpeciesBitset<T> = BoundedVec<u8, SpeciesBitsetLen<T>>;
+
+/// Bytes needed for a bit for every species id below `MaxSpecies`
+pub struct SpeciesBitsetLen<T>(PhantomData<T>);
+impl<T: crate::Config> Get<u32> for SpeciesBitsetLen<T> {
+    fn get() -> u32 {
+        T::MaxSpecies::get().div_ceil(8)
+    }
+}
 
 // population size by jokeymon id
 pub type RegionPopulationDemographics<T> =
@@ -91,7 +134,10 @@ pub struct Region<T: crate::Config> {
 #[serde(bound(serialize = "", deserialize = ""))]
 pub struct AccountData<T: crate::Config> {
     pub current_region: RegionId,
+    /// Every jokeymon the account owns, those not in the party are in the box
     pub jokeymon: BoundedVec<JokeymonId, T::MaxJokeymonHoldable>,
+    /// The active jokeymon, in battle order
+    pub party: BoundedVec<JokeymonId, T::MaxTeamSize>,
 }
 
 /// The jokeymon data associated with a unique jokeymon
@@ -140,3 +186,308 @@ pub struct JokeymonSpeciesData {
     pub diet: Diet,
     pub evolves_to: Option<JokeymonSpeciesId>,
 }
+
+impl JokeymonSpeciesData {
+    /// Herbivores have to eat for their region's carrying capacity to be defined
+    pub fn has_food_intake(&self) -> bool {
+        self.diet != Diet::Herbivore || self.avg_daily_food_consumption > 0
+    }
+}
+
+/// What is keeping a jokeymon from being moved or released
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum LockReason {
+    /// Locked through the nonfungibles `Transfer` trait
+    Transfer,
+    /// Listed for sale on the marketplace
+    Listing,
+    /// Up for auction
+    Auction,
+    /// Staked in a pending battle challenge
+    Challenge,
+    /// Defending a gym
+    Gym,
+}
+
+/// A jokeymon listed for sale on the marketplace
+#[derive(
+    CloneNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    RuntimeDebugNoBound,
+    Encode,
+    Decode,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct Listing<T: crate::Config> {
+    pub seller: T::AccountId,
+    pub price: BalanceOf<T>,
+}
+
+/// How the price of an auctioned jokeymon is found
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum AuctionKind<Balance> {
+    /// Ascending bids, the highest bid wins when the auction ends
+    English { min_bid: Balance },
+    /// Price decays linearly from `start_price` to `floor_price`, the first bid wins
+    Dutch {
+        start_price: Balance,
+        floor_price: Balance,
+    },
+}
+
+/// A running jokeymon auction
+#[derive(
+    CloneNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    RuntimeDebugNoBound,
+    Encode,
+    Decode,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct Auction<T: crate::Config> {
+    pub seller: T::AccountId,
+    pub kind: AuctionKind<BalanceOf<T>>,
+    pub start: BlockNumberFor<T>,
+    pub end: BlockNumberFor<T>,
+    pub highest_bid: Option<(T::AccountId, BalanceOf<T>)>,
+}
+
+/// A pending battle challenge
+#[derive(
+    CloneNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    RuntimeDebugNoBound,
+    Encode,
+    Decode,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct Challenge<T: crate::Config> {
+    pub challenger: T::AccountId,
+    pub team: Team<T>,
+    pub wager: BalanceOf<T>,
+}
+
+/// The battle history of an account
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen, Default)]
+pub struct BattleRecord {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+/// The species an account has seen and caught
+#[derive(
+    CloneNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    RuntimeDebugNoBound,
+    Encode,
+    Decode,
+    TypeInfo,
+    MaxEncodedLen,
+    DefaultNoBound,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct Pokedex<T: crate::Config> {
+    pub seen: SpeciesBitset<T>,
+    pub caught: SpeciesBitset<T>,
+}
+
+/// What an account has to do to complete a quest
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum QuestObjective {
+    /// Catch `count` jokeymon, optionally only of a species, a diet or in a region
+    Catch {
+        species_id: Option<JokeymonSpeciesId>,
+        diet: Option<Diet>,
+        region_id: Option<RegionId>,
+        count: u32,
+    },
+}
+
+/// What an account is paid for completing a quest
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum QuestReward<Balance> {
+    /// Tokens paid from the pallet pot
+    Tokens(Balance),
+    /// A new jokeymon of the species
+    Jokeymon(JokeymonSpeciesId),
+}
+
+/// A quest defined by governance
+#[derive(
+    CloneNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    RuntimeDebugNoBound,
+    Encode,
+    Decode,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct Quest<T: crate::Config> {
+    pub objective: QuestObjective,
+    pub reward: QuestReward<BalanceOf<T>>,
+}
+
+/// An account's progress on a quest
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen, Default)]
+pub struct QuestProgress {
+    pub count: u32,
+    pub claimed: bool,
+}
+
+/// An account's catch allowance, one point is spent per catch and one regained every
+/// `CatchStaminaRegen` blocks
+#[derive(
+    CloneNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    RuntimeDebugNoBound,
+    Encode,
+    Decode,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct CatchStamina<T: crate::Config> {
+    pub points: u32,
+    /// The block `points` were counted at
+    pub updated: BlockNumberFor<T>,
+}
+
+/// The populations of a region at a sampled block
+#[derive(
+    CloneNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    RuntimeDebugNoBound,
+    Encode,
+    Decode,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct PopulationSnapshot<T: crate::Config> {
+    pub block: BlockNumberFor<T>,
+    pub population_demographics: RegionPopulationDemographics<T>,
+}
+
+/// A gym hosted by a region, held by whoever beat the last defending team
+#[derive(
+    CloneNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    RuntimeDebugNoBound,
+    Encode,
+    Decode,
+    TypeInfo,
+    MaxEncodedLen,
+    DefaultNoBound,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct Gym<T: crate::Config> {
+    pub controller: Option<T::AccountId>,
+    pub team: Team<T>,
+    /// Block the controller was last paid up to
+    pub paid_until: BlockNumberFor<T>,
+}
+
+/// A guild of players
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen, Default)]
+pub struct Guild {
+    pub members: u32,
+    /// Jokeymon caught by members while in the guild
+    pub catches: u64,
+}
+
+/// What a guild member is allowed to do, each role can do everything the ones below it can
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    RuntimeDebug,
+    Encode,
+    Decode,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+pub enum GuildRole {
+    /// Fund the guild pot and borrow from the roster
+    Member,
+    /// Manage the roster and remove members
+    Officer,
+    /// Set roles and spend the guild pot
+    Leader,
+}
+
+/// An account's membership of a guild
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct GuildMember {
+    pub guild_id: GuildId,
+    pub role: GuildRole,
+    /// Roster jokeymon the member currently has borrowed
+    pub borrowed: u32,
+}
+
+/// Coefficients of the Lotka-Volterra model regions' populations follow
+///
+/// Herbivores grow at `alpha` up to the region's carrying capacity and are eaten at `beta`,
+/// carnivores grow at `delta` from what they eat and die at `gamma`. Changes are divided by
+/// `timestep` before they are applied.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct PopulationModel {
+    pub timestep: i32,
+    pub alpha: i32,
+    pub beta: i32,
+    pub delta: i32,
+    pub gamma: i32,
+}
+
+impl PopulationModel {
+    /// The coefficients used on chain
+    pub const DEFAULT: Self = Self {
+        timestep: 10_000,
+        alpha: 200,
+        beta: 1,
+        delta: 1,
+        gamma: 200,
+    };
+
+    /// Herbivores and carnivores a region settles at for a herbivore carrying capacity
+    ///
+    /// Carnivores hold the herbivores at `gamma / delta`, and herbivores hold the carnivores at
+    /// `alpha / beta * (1 - herbivores / capacity)`. Carnivores starve when the capacity is at
+    /// or below `gamma / delta`, and the herbivores settle at the capacity instead.
+    pub fn equilibrium(&self, carrying_capacity: u64) -> (u64, u64) {
+        if self.delta <= 0 || self.beta <= 0 {
+            return (carrying_capacity, 0);
+        }
+        let herbivores = self.gamma.max(0) as u64 / self.delta as u64;
+        if herbivores >= carrying_capacity {
+            return (carrying_capacity, 0);
+        }
+        let carnivores = (self.alpha.max(0) as u128 * (carrying_capacity - herbivores) as u128)
+            / (self.beta as u128 * carrying_capacity as u128);
+        (herbivores, carnivores as u64)
+    }
+}
+
+impl Default for PopulationModel {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
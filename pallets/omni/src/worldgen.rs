@@ -0,0 +1,225 @@
+//! Deterministic world generation from a seed
+//!
+//! The same seed and sizes always give the same world. Regions sit on a grid, each adjacent to
+//! its neighbours along the grid, and every region starts at the equilibrium of the
+//! on-chain [`PopulationModel`], so its populations don't move until players start catching and
+//! releasing.
+//!
+//! Carnivores stop changing at `gamma / delta` herbivores, and herbivores stop changing at
+//! `alpha / beta * (1 - herbivores / K)` carnivores for a carrying capacity `K`. Capacities are
+//! picked so that number is whole.
+
+use crate::{
+    types::{
+        Coordinate, Diet, JokeymonCount, JokeymonSpeciesData, JokeymonSpeciesId, PopulationModel,
+        Region, RegionId,
+    },
+    Config, GenesisConfig,
+};
+use frame_support::{traits::Get, BoundedBTreeMap};
+use scale_info::prelude::collections::BTreeMap;
+use sp_runtime::Vec;
+
+/// Herbivores in a region at equilibrium
+pub const EQUILIBRIUM_HERBIVORES: u32 =
+    (PopulationModel::DEFAULT.gamma / PopulationModel::DEFAULT.delta) as u32;
+
+/// Carrying capacities with a whole number of carnivores at equilibrium
+const CARRYING_CAPACITIES: [u32; 4] = [400, 500, 1_000, 2_000];
+
+/// Sizes and seed of a generated world
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorldParams {
+    pub seed: u64,
+    /// Regions along the longitude
+    pub width: u32,
+    /// Regions along the latitude
+    pub height: u32,
+    pub herbivores: u32,
+    pub carnivores: u32,
+}
+
+/// The regions, adjacency and species of a generated world
+pub struct GeneratedWorld<T: Config> {
+    pub regions: Vec<Region<T>>,
+    /// Each region with the regions next to it on the grid
+    pub adjacency: Vec<(RegionId, Vec<RegionId>)>,
+    pub species: Vec<JokeymonSpeciesData>,
+}
+
+impl<T: Config> GeneratedWorld<T> {
+    /// The pallet's genesis config for the world, adjacency is not stored on chain
+    pub fn genesis_config(&self) -> GenesisConfig<T> {
+        GenesisConfig {
+            region_id_to_region: self
+                .regions
+                .iter()
+                .map(|region| (region.id, region.clone()))
+                .collect(),
+            species_id_to_data: self
+                .species
+                .iter()
+                .map(|species| (species.id, species.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// A splitmix64 generator, small and the same on every platform
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// a number in `low..=high`
+    fn range(&mut self, low: u32, high: u32) -> u32 {
+        low + (self.next() % (high - low + 1) as u64) as u32
+    }
+
+    /// `count` distinct picks from `0..len`, sorted
+    fn pick(&mut self, len: u32, count: u32) -> Vec<u32> {
+        let mut all: Vec<u32> = (0..len).collect();
+        for i in 0..count.min(len) as usize {
+            let j = i + (self.next() % (all.len() - i) as u64) as usize;
+            all.swap(i, j);
+        }
+        all.truncate(count as usize);
+        all.sort();
+        all
+    }
+}
+
+/// Generate a world, needs at least one herbivore and one carnivore
+pub fn generate<T: Config>(params: &WorldParams) -> GeneratedWorld<T> {
+    assert!(
+        params.herbivores > 0 && params.carnivores > 0,
+        "a world needs herbivores and carnivores"
+    );
+    let mut rng = Rng(params.seed);
+
+    let carnivore_ids = params.herbivores..params.herbivores + params.carnivores;
+    let species: Vec<_> = (0..params.herbivores)
+        .map(|id| (id, Diet::Herbivore, 0..params.herbivores))
+        .chain(
+            carnivore_ids
+                .clone()
+                .map(|id| (id, Diet::Carnivore, carnivore_ids.clone())),
+        )
+        .map(|(id, diet, same_diet)| {
+            let (avg_weight, avg_daily_food_consumption) = match diet {
+                Diet::Herbivore => (rng.range(5, 50), rng.range(5, 30)),
+                Diet::Carnivore => (rng.range(20, 80), rng.range(20, 60)),
+            };
+            // only evolving into higher ids keeps evolution free of cycles
+            let evolves_to =
+                (id + 1 < same_diet.end && rng.next().is_multiple_of(2)).then_some(id + 1);
+            JokeymonSpeciesData {
+                id,
+                avg_weight: avg_weight as u16,
+                avg_daily_food_consumption: avg_daily_food_consumption as u16,
+                diet,
+                evolves_to,
+            }
+        })
+        .collect();
+
+    let max_species = T::MaxSpeciesInRegion::get().max(2);
+    let mut regions = Vec::new();
+    let mut adjacency = Vec::new();
+    for latitude in 0..params.height {
+        for longitude in 0..params.width {
+            let id = latitude * params.width + longitude;
+            let herbivore_count = rng.range(1, params.herbivores.min(max_species - 1).min(4));
+            let carnivore_count = rng.range(
+                1,
+                params.carnivores.min(max_species - herbivore_count).min(2),
+            );
+            let herbivores = rng.pick(params.herbivores, herbivore_count);
+            let carnivores: Vec<_> = rng
+                .pick(params.carnivores, carnivore_count)
+                .into_iter()
+                .map(|index| params.herbivores + index)
+                .collect();
+
+            // the pallet's carrying capacity is the energy yield over the average intake
+            let intake = herbivores
+                .iter()
+                .map(|id| species[*id as usize].avg_daily_food_consumption as u32)
+                .sum::<u32>()
+                / herbivore_count;
+            let capacity =
+                CARRYING_CAPACITIES[(rng.next() % CARRYING_CAPACITIES.len() as u64) as usize];
+            let (_, carnivore_total) = PopulationModel::DEFAULT.equilibrium(capacity as u64);
+
+            let mut demographics = BTreeMap::new();
+            demographics.extend(split(EQUILIBRIUM_HERBIVORES, &herbivores));
+            demographics.extend(split(carnivore_total as u32, &carnivores));
+            regions.push(region(
+                id,
+                latitude,
+                longitude,
+                capacity * intake,
+                demographics,
+            ));
+
+            let mut neighbours = Vec::new();
+            if latitude > 0 {
+                neighbours.push(id - params.width);
+            }
+            if longitude > 0 {
+                neighbours.push(id - 1);
+            }
+            if longitude + 1 < params.width {
+                neighbours.push(id + 1);
+            }
+            if latitude + 1 < params.height {
+                neighbours.push(id + params.width);
+            }
+            adjacency.push((id, neighbours));
+        }
+    }
+
+    GeneratedWorld {
+        regions,
+        adjacency,
+        species,
+    }
+}
+
+/// `total` shared as evenly as possible between `species`
+fn split(
+    total: JokeymonCount,
+    species: &[JokeymonSpeciesId],
+) -> impl Iterator<Item = (JokeymonSpeciesId, JokeymonCount)> + '_ {
+    let count = species.len() as u32;
+    species.iter().enumerate().map(move |(index, id)| {
+        (
+            *id,
+            total / count + u32::from((index as u32) < total % count),
+        )
+    })
+}
+
+fn region<T: Config>(
+    id: RegionId,
+    latitude: Coordinate,
+    longitude: Coordinate,
+    energy_yield: u32,
+    demographics: BTreeMap<JokeymonSpeciesId, JokeymonCount>,
+) -> Region<T> {
+    Region {
+        id,
+        total_population: demographics.values().map(|count| *count as u64).sum(),
+        population_demographics: BoundedBTreeMap::try_from(demographics)
+            .expect("species per region are capped by MaxSpeciesInRegion; qed"),
+        energy_yield,
+        latitude,
+        longitude,
+    }
+}
@@ -36,15 +36,15 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
     traits::Block as BlockT,
     transaction_validity::{TransactionSource, TransactionValidity},
-    ApplyExtrinsicResult,
+    ApplyExtrinsicResult, Permill,
 };
 use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-    AccountId, Balance, Block, ConsensusHook, Executive, InherentDataExt, Nonce, ParachainSystem,
-    Runtime, RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment,
-    SLOT_DURATION, VERSION,
+    genesis_config_presets, AccountId, Balance, Block, BlockNumber, ConsensusHook, Executive,
+    InherentDataExt, Nonce, OmniPallet, ParachainSystem, Runtime, RuntimeCall,
+    RuntimeGenesisConfig, SessionKeys, System, TransactionPayment, SLOT_DURATION, VERSION,
 };
 
 // we move some impls outside so we can easily use them with `docify`.
@@ -289,17 +289,37 @@ impl_runtime_apis! {
         }
     }
 
+    #[api_version(2)]
+    impl pallet_omni::runtime_api::OmniApi<Block, AccountId, BlockNumber> for Runtime {
+        fn leaderboard(page: u32, page_size: u32) -> Vec<(AccountId, pallet_omni::types::Rating)> {
+            OmniPallet::leaderboard_page(page, page_size)
+        }
+
+        fn pokedex_completion(who: AccountId) -> Vec<(pallet_omni::types::RegionId, Permill)> {
+            OmniPallet::pokedex_completion(&who)
+        }
+
+        fn population_history(
+            region_id: pallet_omni::types::RegionId,
+        ) -> Vec<(
+            BlockNumber,
+            alloc::collections::BTreeMap<pallet_omni::types::JokeymonSpeciesId, pallet_omni::types::JokeymonCount>,
+        )> {
+            OmniPallet::population_history(region_id)
+        }
+    }
+
     impl sp_genesis_builder::GenesisBuilder<Block> for Runtime {
         fn build_state(config: Vec<u8>) -> sp_genesis_builder::Result {
             build_state::<RuntimeGenesisConfig>(config)
         }
 
         fn get_preset(id: &Option<sp_genesis_builder::PresetId>) -> Option<Vec<u8>> {
-            get_preset::<RuntimeGenesisConfig>(id, |_| None)
+            get_preset::<RuntimeGenesisConfig>(id, genesis_config_presets::get_preset)
         }
 
         fn preset_names() -> Vec<sp_genesis_builder::PresetId> {
-            Default::default()
+            genesis_config_presets::preset_names()
         }
     }
 }
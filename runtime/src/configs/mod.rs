@@ -48,7 +48,7 @@ use polkadot_runtime_common::{
     xcm_sender::NoPriceForMessageDelivery, BlockHashCount, SlowAdjustingFeeUpdate,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_runtime::Perbill;
+use sp_runtime::{traits::AccountIdConversion, Perbill, Permill};
 use sp_version::RuntimeVersion;
 use xcm::latest::prelude::BodyId;
 
@@ -56,10 +56,11 @@ use xcm::latest::prelude::BodyId;
 use super::{
     weights::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight},
     AccountId, Aura, Balance, Balances, Block, BlockNumber, CollatorSelection, ConsensusHook, Hash,
-    MessageQueue, Nonce, PalletInfo, ParachainSystem, RandomPallet, Runtime, RuntimeCall,
-    RuntimeEvent, RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask, Session,
-    SessionKeys, System, WeightToFee, XcmpQueue, AVERAGE_ON_INITIALIZE_RATIO, EXISTENTIAL_DEPOSIT,
-    HOURS, MAXIMUM_BLOCK_WEIGHT, MICROUNIT, NORMAL_DISPATCH_RATIO, SLOT_DURATION, VERSION,
+    MessageQueue, MultiBlockMigrations, Nonce, PalletInfo, ParachainSystem, RandomPallet, Runtime,
+    RuntimeCall, RuntimeEvent, RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask,
+    Session, SessionKeys, System, WeightToFee, XcmpQueue, AVERAGE_ON_INITIALIZE_RATIO, DAYS,
+    EXISTENTIAL_DEPOSIT, HOURS, MAXIMUM_BLOCK_WEIGHT, MICROUNIT, MILLIUNIT, MINUTES,
+    NORMAL_DISPATCH_RATIO, SLOT_DURATION, UNIT, VERSION,
 };
 use xcm_config::{RelayLocation, XcmOriginToTransactDispatchOrigin};
 
@@ -123,6 +124,8 @@ impl frame_system::Config for Runtime {
     /// The action to take on a Runtime Upgrade
     type OnSetCode = cumulus_pallet_parachain_system::ParachainSetCode<Self>;
     type MaxConsumers = frame_support::traits::ConstU32<16>;
+    /// Runs the stepped storage migrations across blocks
+    type MultiBlockMigrator = MultiBlockMigrations;
 }
 
 impl pallet_timestamp::Config for Runtime {
@@ -229,6 +232,27 @@ impl pallet_message_queue::Config for Runtime {
     type IdleMaxServiceWeight = ();
 }
 
+parameter_types! {
+    pub MbmServiceWeight: Weight = Perbill::from_percent(80) * RuntimeBlockWeights::get().max_block;
+}
+
+impl pallet_migrations::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    #[cfg(not(feature = "runtime-benchmarks"))]
+    type Migrations = (
+        pallet_omni::migrations::v1::MigrateV0ToV1<Runtime>,
+        pallet_omni::migrations::v2::MigrateV1ToV2<Runtime>,
+    );
+    #[cfg(feature = "runtime-benchmarks")]
+    type Migrations = pallet_migrations::mock_helpers::MockedMigrations;
+    type CursorMaxLen = ConstU32<65_536>;
+    type IdentifierMaxLen = ConstU32<256>;
+    type MigrationStatusHandler = ();
+    type FailedMigrationHandler = frame_support::migrations::FreezeChainOnFailedMigration;
+    type MaxServiceWeight = MbmServiceWeight;
+    type WeightInfo = pallet_migrations::weights::SubstrateWeight<Runtime>;
+}
+
 impl cumulus_pallet_aura_ext::Config for Runtime {}
 
 impl cumulus_pallet_xcmp_queue::Config for Runtime {
@@ -309,6 +333,33 @@ impl pallet_insecure_randomness_collective_flip::Config for Runtime {}
 parameter_types! {
     pub const MaxJokeymonInRegion : u32 = 50;
     pub const MaxJokeymonHoldable : u32 = 100;
+    pub const OmniPalletId: PalletId = PalletId(*b"jk/omni_");
+    pub const CatchFee: Balance = 10 * MILLIUNIT;
+    pub const TravelFee: Balance = 5 * MILLIUNIT;
+    pub const JokeymonDeposit: Balance = 10 * MILLIUNIT;
+    pub const MarketplaceFee: Permill = Permill::from_percent(2);
+    pub TreasuryAccount: AccountId = PalletId(*b"jk/trsry").into_account_truncating();
+    pub const MaxActiveAuctions: u32 = 256;
+    pub const MaxAuctionDuration: BlockNumber = 7 * DAYS;
+    pub const MaxTeamSize: u32 = 6;
+    pub const MaxBattleRounds: u32 = 100;
+    pub const SeasonLength: BlockNumber = 28 * DAYS;
+    pub const LeaderboardSize: u32 = 100;
+    pub const SeasonReward: Balance = 100 * UNIT;
+    pub const MaxSpecies: u32 = 1024;
+    pub const PokedexMilestone: u32 = 10;
+    pub const PokedexReward: Balance = 5 * UNIT;
+    pub const MaxQuests: u32 = 64;
+    pub const GymReward: Balance = MILLIUNIT;
+    pub const MaxGuildMembers: u32 = 50;
+    pub const PopulationSampleInterval: BlockNumber = HOURS;
+    pub const PopulationHistoryLength: u32 = 7 * 24;
+    pub const MaxRegionsSampledPerBlock: u32 = 100;
+    pub const ProtectionThreshold: Permill = Permill::from_percent(2);
+    pub const MaxCatchStamina: u32 = 20;
+    pub const CatchStaminaRegen: BlockNumber = 3 * MINUTES;
+    pub const RatedMatchCooldown: BlockNumber = HOURS;
+    pub const BattleExperience: u32 = 60;
 }
 
 /// Configure the omni pallet
@@ -318,4 +369,34 @@ impl pallet_omni::Config for Runtime {
     type RandomSource = RandomPallet;
     type MaxSpeciesInRegion = MaxJokeymonInRegion;
     type MaxJokeymonHoldable = MaxJokeymonHoldable;
+    type Currency = Balances;
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type PalletId = OmniPalletId;
+    type CatchFee = CatchFee;
+    type TravelFee = TravelFee;
+    type JokeymonDeposit = JokeymonDeposit;
+    type MarketplaceFee = MarketplaceFee;
+    type TreasuryAccount = TreasuryAccount;
+    type MaxActiveAuctions = MaxActiveAuctions;
+    type MaxAuctionDuration = MaxAuctionDuration;
+    type MaxTeamSize = MaxTeamSize;
+    type MaxBattleRounds = MaxBattleRounds;
+    type SeasonLength = SeasonLength;
+    type LeaderboardSize = LeaderboardSize;
+    type SeasonReward = SeasonReward;
+    type MaxSpecies = MaxSpecies;
+    type PokedexMilestone = PokedexMilestone;
+    type PokedexReward = PokedexReward;
+    type AdminOrigin = EnsureRoot<AccountId>;
+    type MaxQuests = MaxQuests;
+    type GymReward = GymReward;
+    type MaxGuildMembers = MaxGuildMembers;
+    type PopulationSampleInterval = PopulationSampleInterval;
+    type PopulationHistoryLength = PopulationHistoryLength;
+    type MaxRegionsSampledPerBlock = MaxRegionsSampledPerBlock;
+    type ProtectionThreshold = ProtectionThreshold;
+    type MaxCatchStamina = MaxCatchStamina;
+    type CatchStaminaRegen = CatchStaminaRegen;
+    type RatedMatchCooldown = RatedMatchCooldown;
+    type BattleExperience = BattleExperience;
 }
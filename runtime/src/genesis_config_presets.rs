@@ -0,0 +1,305 @@
+//! Named genesis presets, one per jokeymon world
+//!
+//! Every preset has the same development accounts and collators, they only differ in the
+//! regions and species given to the omni pallet.
+
+use crate::{AccountId, AuraId, Runtime, SessionKeys, EXISTENTIAL_DEPOSIT};
+use alloc::{vec, vec::Vec};
+use cumulus_primitives_core::ParaId;
+use pallet_omni::types::{
+    Coordinate, Diet, JokeymonCount, JokeymonSpeciesData, JokeymonSpeciesId, Region, RegionId,
+};
+use sp_genesis_builder::PresetId;
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::BoundedBTreeMap;
+
+/// One region with three species, the world used by `dev` and `local` chains
+pub const DEV_SMALL_WORLD: &str = "dev-small-world";
+/// A thousand regions, each with as many species as a region can hold
+pub const STRESS_1000_REGIONS: &str = "stress-1000-regions";
+/// Nine regions whose populations start at the predator prey equilibrium
+pub const BALANCED_ECOSYSTEM: &str = "balanced-ecosystem";
+
+/// The para id every preset is registered under
+const PARA_ID: u32 = 1000;
+
+/// The default XCM version to set in genesis config.
+const SAFE_XCM_VERSION: u32 = xcm::prelude::XCM_VERSION;
+
+/// The regions and species of a world
+pub struct World {
+    pub regions: Vec<Region<Runtime>>,
+    pub species: Vec<JokeymonSpeciesData>,
+}
+
+impl World {
+    /// The `omniPallet` section of a genesis config
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "regionIdToRegion": self
+                .regions
+                .iter()
+                .map(|region| (region.id, region))
+                .collect::<Vec<_>>(),
+            "speciesIdToData": self
+                .species
+                .iter()
+                .map(|species| (species.id, species))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// A genesis config patch for a world, with the development accounts and collators
+pub fn testnet_genesis(
+    invulnerables: Vec<(AccountId, AuraId)>,
+    endowed_accounts: Vec<AccountId>,
+    root: AccountId,
+    id: ParaId,
+    world: &World,
+) -> serde_json::Value {
+    serde_json::json!({
+        "balances": {
+            "balances": endowed_accounts.iter().cloned().map(|k| (k, 1u64 << 60)).collect::<Vec<_>>(),
+        },
+        "parachainInfo": {
+            "parachainId": id,
+        },
+        "collatorSelection": {
+            "invulnerables": invulnerables.iter().cloned().map(|(acc, _)| acc).collect::<Vec<_>>(),
+            "candidacyBond": EXISTENTIAL_DEPOSIT * 16,
+        },
+        "session": {
+            "keys": invulnerables
+                .into_iter()
+                .map(|(acc, aura)| {
+                    (
+                        acc.clone(),         // account id
+                        acc,                 // validator id
+                        SessionKeys { aura }, // session keys
+                    )
+                })
+                .collect::<Vec<_>>(),
+        },
+        "polkadotXcm": {
+            "safeXcmVersion": Some(SAFE_XCM_VERSION),
+        },
+        "sudo": { "key": Some(root) },
+        "omniPallet": world.to_json(),
+    })
+}
+
+/// The genesis config patch for a world on the development accounts
+pub fn development_genesis(world: &World) -> serde_json::Value {
+    testnet_genesis(
+        vec![
+            (
+                Sr25519Keyring::Alice.to_account_id(),
+                Sr25519Keyring::Alice.public().into(),
+            ),
+            (
+                Sr25519Keyring::Bob.to_account_id(),
+                Sr25519Keyring::Bob.public().into(),
+            ),
+        ],
+        Sr25519Keyring::iter()
+            .filter(|k| !matches!(k, Sr25519Keyring::One | Sr25519Keyring::Two))
+            .map(|k| k.to_account_id())
+            .collect(),
+        Sr25519Keyring::Alice.to_account_id(),
+        PARA_ID.into(),
+        world,
+    )
+}
+
+/// The world of a named preset
+pub fn preset_world(id: &str) -> Option<World> {
+    match id {
+        DEV_SMALL_WORLD => Some(dev_small_world()),
+        STRESS_1000_REGIONS => Some(stress_1000_regions()),
+        BALANCED_ECOSYSTEM => Some(balanced_ecosystem()),
+        _ => None,
+    }
+}
+
+/// The genesis config patch of a named preset
+pub fn preset(id: &str) -> Option<serde_json::Value> {
+    preset_world(id).map(|world| development_genesis(&world))
+}
+
+/// Serialized patch of a named preset, for `GenesisBuilder::get_preset`
+pub fn get_preset(id: &PresetId) -> Option<Vec<u8>> {
+    let id: &str = id.try_into().ok()?;
+    let patch = preset(id)?;
+    Some(
+        serde_json::to_string(&patch)
+            .expect("serialization to json is expected to work. qed.")
+            .into_bytes(),
+    )
+}
+
+/// Names of every preset
+pub fn preset_names() -> Vec<PresetId> {
+    vec![
+        PresetId::from(DEV_SMALL_WORLD),
+        PresetId::from(STRESS_1000_REGIONS),
+        PresetId::from(BALANCED_ECOSYSTEM),
+    ]
+}
+
+/// a region whose total population is the sum of `demographics`
+fn region(
+    id: RegionId,
+    latitude: Coordinate,
+    longitude: Coordinate,
+    energy_yield: u32,
+    demographics: impl IntoIterator<Item = (JokeymonSpeciesId, JokeymonCount)>,
+) -> Region<Runtime> {
+    let population_demographics = BoundedBTreeMap::try_from(
+        demographics
+            .into_iter()
+            .collect::<alloc::collections::BTreeMap<_, _>>(),
+    )
+    .expect("presets keep within MaxSpeciesInRegion; qed");
+    Region {
+        id,
+        total_population: population_demographics
+            .values()
+            .map(|count| *count as u64)
+            .sum(),
+        population_demographics,
+        energy_yield,
+        latitude,
+        longitude,
+    }
+}
+
+fn species(
+    id: JokeymonSpeciesId,
+    avg_weight: u16,
+    avg_daily_food_consumption: u16,
+    diet: Diet,
+    evolves_to: Option<JokeymonSpeciesId>,
+) -> JokeymonSpeciesData {
+    JokeymonSpeciesData {
+        id,
+        avg_weight,
+        avg_daily_food_consumption,
+        diet,
+        evolves_to,
+    }
+}
+
+fn dev_small_world() -> World {
+    World {
+        regions: vec![region(0, 0, 0, 100_000, [(0, 150), (1, 150), (2, 150)])],
+        species: vec![
+            species(0, 10, 10, Diet::Herbivore, Some(1)),
+            species(1, 20, 25, Diet::Herbivore, Some(2)),
+            species(2, 30, 45, Diet::Carnivore, None),
+        ],
+    }
+}
+
+/// 1000 regions on a 40 by 25 grid, each holding 50 of 256 species
+fn stress_1000_regions() -> World {
+    const SPECIES: u32 = 256;
+    const SPECIES_PER_REGION: u32 = 50;
+
+    // every fourth species is a carnivore, the others evolve into the next species
+    let species = (0..SPECIES)
+        .map(|id| {
+            let diet = if id % 4 == 3 {
+                Diet::Carnivore
+            } else {
+                Diet::Herbivore
+            };
+            let evolves_to = (id % 4 != 3).then_some(id + 1);
+            species(
+                id,
+                10 + (id % 50) as u16,
+                5 + (id % 40) as u16,
+                diet,
+                evolves_to,
+            )
+        })
+        .collect();
+
+    let regions = (0..1000u32)
+        .map(|id| {
+            let first = id * 7 % SPECIES;
+            region(
+                id,
+                id / 40,
+                id % 40,
+                50_000 + id * 97 % 50_000,
+                (0..SPECIES_PER_REGION)
+                    .map(|k| ((first + k) % SPECIES, 100 + (id * 31 + k * 17) % 400)),
+            )
+        })
+        .collect();
+
+    World { regions, species }
+}
+
+/// Nine regions on a 3 by 3 grid at the equilibrium of the pallet's Lotka-Volterra model
+///
+/// Carnivores stop changing at 200 herbivores, herbivores stop changing at 100 carnivores
+/// when the carrying capacity is 400, that is an energy yield of 4000 for an average intake
+/// of 10.
+fn balanced_ecosystem() -> World {
+    let species = vec![
+        species(0, 8, 8, Diet::Herbivore, Some(1)),
+        species(1, 12, 12, Diet::Herbivore, None),
+        species(2, 9, 9, Diet::Herbivore, Some(3)),
+        species(3, 11, 11, Diet::Herbivore, None),
+        species(4, 30, 40, Diet::Carnivore, Some(5)),
+        species(5, 45, 60, Diet::Carnivore, None),
+    ];
+    let regions = (0..9u32)
+        .map(|id| {
+            region(
+                id,
+                id / 3,
+                id % 3,
+                4_000,
+                [(0, 50), (1, 50), (2, 50), (3, 50), (4, 50), (5, 50)],
+            )
+        })
+        .collect();
+
+    World { regions, species }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RuntimeGenesisConfig;
+    use sp_runtime::BuildStorage;
+
+    /// Apply a JSON merge patch, the way a genesis preset is applied to the default config
+    fn merge(base: &mut serde_json::Value, patch: serde_json::Value) {
+        match (base, patch) {
+            (serde_json::Value::Object(base), serde_json::Value::Object(patch)) => {
+                for (key, value) in patch {
+                    merge(base.entry(key).or_insert(serde_json::Value::Null), value);
+                }
+            }
+            (base, patch) => *base = patch,
+        }
+    }
+
+    #[test]
+    fn every_preset_builds_a_consistent_genesis() {
+        for id in preset_names() {
+            let patch = get_preset(&id).expect("every listed preset exists");
+            let mut config = serde_json::to_value(RuntimeGenesisConfig::default()).unwrap();
+            merge(&mut config, serde_json::from_slice(&patch).unwrap());
+            let config: RuntimeGenesisConfig = serde_json::from_value(config)
+                .unwrap_or_else(|e| panic!("preset {:?} does not parse: {}", id, e));
+
+            // building the omni pallet's genesis asserts its regions and species are consistent
+            config.build_storage().unwrap();
+        }
+    }
+}